@@ -1,10 +1,21 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse_macro_input, punctuated::Punctuated, spanned::Spanned, AngleBracketedGenericArguments,
-    Attribute, Data, DataStruct, DeriveInput, Error, Expr, Fields, FieldsNamed, GenericArgument,
-    Ident, Lit, MetaNameValue, Path, PathArguments, PathSegment, Result, Token, Type, TypePath,
+    ext::IdentExt,
+    meta::ParseNestedMeta,
+    parenthesized,
+    parse::Parse,
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+    AngleBracketedGenericArguments, Attribute, Data, DataEnum, DataStruct, DeriveInput, Error,
+    Expr, ExprLit, Field, FieldMutability, Fields, FieldsNamed, FnArg, GenericArgument,
+    GenericParam, Generics, Ident, Index, ItemFn, Lifetime, Lit, LitBool, LitStr, Member, Meta,
+    MetaNameValue, ParenthesizedGenericArguments, Pat, Path, PathArguments, PathSegment, Result,
+    ReturnType, Token, Type, TypeBareFn, TypeGenerics, TypeImplTrait, TypePath, TypeReference,
+    Visibility,
 };
 
 /*
@@ -67,144 +78,2602 @@ pub fn derive_builder(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_attribute]
+pub fn builder(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = TokenStream2::from(args);
+    let item: ItemFn = parse_macro_input!(input as ItemFn);
+
+    match impl_fn_builder(args, item.clone()) {
+        Ok(token_stream) => TokenStream::from(token_stream),
+        // 関数を使用している箇所でエラーが続かないように、関数はそのまま出力する
+        Err(err) => {
+            let mut item = item;
+            for input in &mut item.sig.inputs {
+                if let FnArg::Typed(pat_type) = input {
+                    pat_type
+                        .attrs
+                        .retain(|attr| !attr.path().is_ident("builder"));
+                }
+            }
+            let err = err.into_compile_error();
+            TokenStream::from(quote! { #err #item })
+        }
+    }
+}
+
 fn impl_builder(input: DeriveInput) -> Result<TokenStream2> {
-    if let Data::Struct(DataStruct {
-        fields:
-            Fields::Named(FieldsNamed {
-                named: named_fields,
-                ..
-            }),
-        ..
-    }) = input.data
-    {
-        let ident = input.ident;
-        let builder_ident = format_ident!("{}Builder", ident);
-
-        // ビルダーを作成する対象の構造体のフィールド名とフィールドの型を取得
-        let mut fields: Vec<(Ident, Type)> = vec![];
-        for name_field in named_fields.iter() {
-            fields.push((
-                name_field.ident.as_ref().unwrap().clone(),
-                name_field.ty.clone(),
+    let struct_attrs = inspect_struct_attrs(&input.attrs)?;
+    let ident = &input.ident;
+    // ビルダーの可視性は、指定されていない場合は構造体の可視性に従う
+    let vis = struct_attrs.vis.as_ref().unwrap_or(&input.vis);
+
+    // ビルダーを作成する対象を取得
+    // 構造体の場合は構造体自身、列挙型の場合はフィールドを持つヴァリアントごとにビルダーを作成
+    let targets = match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => {
+            let rename_all = serde_rename_all(&input.attrs, "rename_all")?;
+            let (fields, skipped_fields) = builder_fields(fields, &struct_attrs, rename_all)?;
+            let std_lib = struct_attrs.std_lib(&fields);
+            vec![BuilderTarget {
+                builder_ident: struct_attrs
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format_ident!("{}Builder", ident)),
+                vis: vis.clone(),
+                constructor_ident: struct_attrs
+                    .constructor
+                    .clone()
+                    .unwrap_or_else(|| format_ident!("builder")),
+                path: quote! { #ident },
+                fields,
+                skipped_fields,
+                struct_default: struct_attrs.default.is_some(),
+                kind: TargetKind::Struct,
+                std_lib,
+            }]
+        }
+        Data::Enum(DataEnum { variants, .. }) => {
+            if let Some(path) = &struct_attrs.default {
+                return Err(Error::new_spanned(
+                    path,
+                    "`builder(default)` is not supported on enums",
+                ));
+            }
+            // ヴァリアントごとにビルダーを作成するため、名前を1つだけ指定することはできない
+            if let Some(name) = struct_attrs
+                .name
+                .as_ref()
+                .or(struct_attrs.constructor.as_ref())
+            {
+                return Err(Error::new_spanned(
+                    name,
+                    "builder and constructor names cannot be set on enums",
+                ));
+            }
+            // ヴァリアントのフィールド名には、ヴァリアントの`rename_all`を優先して適用する
+            let rename_all_fields = serde_rename_all(&input.attrs, "rename_all_fields")?;
+            variants
+                .iter()
+                .filter(|variant| !matches!(variant.fields, Fields::Unit))
+                .map(|variant| {
+                    let variant_ident = &variant.ident;
+                    let rename_all =
+                        serde_rename_all(&variant.attrs, "rename_all")?.or(rename_all_fields);
+                    let (fields, skipped_fields) =
+                        builder_fields(&variant.fields, &struct_attrs, rename_all)?;
+                    let std_lib = struct_attrs.std_lib(&fields);
+                    Ok(BuilderTarget {
+                        builder_ident: format_ident!("{}{}Builder", ident, variant_ident),
+                        vis: vis.clone(),
+                        constructor_ident: format_ident!(
+                            "{}_builder",
+                            to_snake_case(&variant_ident.unraw().to_string())
+                        ),
+                        path: quote! { #ident::#variant_ident },
+                        fields,
+                        skipped_fields,
+                        struct_default: false,
+                        kind: TargetKind::Variant,
+                        std_lib,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
+        }
+        Data::Union(_) => return Err(Error::new(input.span(), "Union is not supported")),
+    };
+
+    let mut token_stream = TokenStream2::new();
+    for target in &targets {
+        if struct_attrs.typestate {
+            token_stream.extend(impl_typestate_builder(
+                ident,
+                &input.generics,
+                &struct_attrs,
+                target,
+            ));
+        } else if struct_attrs.constant {
+            token_stream.extend(impl_const_builder(
+                ident,
+                &input.generics,
+                &struct_attrs,
+                target,
+            ));
+        } else {
+            token_stream.extend(impl_runtime_builder(
+                ident,
+                &input.generics,
+                &struct_attrs,
+                target,
+            ));
+        }
+    }
+
+    Ok(token_stream)
+}
+
+/// 構造体またはヴァリアントのフィールドから、ビルダーが値を設定するフィールドと、
+/// `builder(skip)`属性でビルダーに含めないフィールドを取得する。
+///
+/// タプル構造体のフィールドは、`builder(name = "...")`属性で名前が指定されていない
+/// 場合、`_0`や`_1`のように位置で名前を付ける。`rename_all`は、serdeで読み書きする
+/// ときのフィールド名に適用する、構造体またはヴァリアントの変換規則である。
+fn builder_fields(
+    fields: &Fields,
+    struct_attrs: &StructAttrs,
+    rename_all: SerdeRenameAll,
+) -> Result<(Vec<BuilderField>, Vec<SkippedField>)> {
+    let mut builder_fields: Vec<BuilderField> = vec![];
+    let mut skipped_fields: Vec<SkippedField> = vec![];
+    for (index, field) in fields.iter().enumerate() {
+        let field_attrs = inspect_field_attrs(&field.attrs, struct_attrs.constant)?;
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        if let Some(value) = field_attrs.skip {
+            // 定数式で構築するビルダーでは、`Default`実装を呼び出せない
+            if struct_attrs.constant && matches!(value, FieldDefault::Trait) {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`builder(const)` requires a constant value; use `builder(skip = \"...\")`",
+                ));
+            }
+            skipped_fields.push(SkippedField { member, value });
+            continue;
+        }
+        let ident = match (field_attrs.name, &field.ident) {
+            (Some(name), _) => name,
+            (None, Some(ident)) => ident.clone(),
+            (None, None) => format_ident!("_{}", index),
+        };
+        // フィールドでセッターの接頭辞が指定されていない場合は、構造体の指定に従う
+        let prefix = field_attrs
+            .setter
+            .prefix
+            .as_ref()
+            .or(struct_attrs.setter.prefix.as_ref())
+            .map(|prefix| prefix.value())
+            .unwrap_or_default();
+        // `r#type`のような生識別子は、接頭辞を付与する場合のみ`r#`を取り除く
+        let setter_ident = if prefix.is_empty() {
+            ident.clone()
+        } else {
+            format_ident!("{}{}", prefix, ident.unraw())
+        };
+        let each = field_attrs.each.map(|each| (each.name, each.item));
+        // 型状態を持つビルダーは、内側のビルダーの必須フィールドを型で表現できない
+        let sub_builder = match field_attrs.sub_builder {
+            Some(_) if struct_attrs.typestate => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`builder(sub_builder)` cannot be used with `builder(typestate)`",
+                ))
+            }
+            Some(Some(ty)) => Some(ty),
+            Some(None) => Some(sub_builder_ty(&field.ty)?),
+            None => None,
+        };
+        let serde_names = rename_all.names(&field.attrs, &member, &ident);
+        let serde_attrs = if struct_attrs.serde {
+            serde_field_attrs(&field.attrs)?
+        } else {
+            vec![]
+        };
+        builder_fields.push(BuilderField {
+            ident,
+            setter_ident,
+            member,
+            ty: field.ty.clone(),
+            each: each.as_ref().map(|(name, _)| name.clone()),
+            each_item: each.and_then(|(_, item)| item),
+            default: field_attrs.default,
+            optional: field_attrs.optional,
+            private: field_attrs.private,
+            try_setter: field_attrs.try_setter,
+            serde_names,
+            serde_attrs,
+            merge: match &field_attrs.merge {
+                _ if sub_builder.is_some() => MergeStrategy::Nested,
+                Some(merge) if merge.value() == "append" => MergeStrategy::Append,
+                _ => MergeStrategy::Replace,
+            },
+            sub_builder,
+            validate: field_attrs.validate,
+            // フィールドでセッターの形式が指定されていない場合は、構造体の指定に従う
+            into: field_attrs
+                .setter
+                .into
+                .or(struct_attrs.setter.into)
+                .unwrap_or(false),
+            strip_option: field_attrs
+                .setter
+                .strip_option
+                .or(struct_attrs.setter.strip_option)
+                .unwrap_or(true),
+            doc: doc_comment(&field.attrs),
+        });
+        let builder_field = builder_fields.last().unwrap();
+        if builder_field.try_setter
+            && builder_field.each.as_ref() == Some(&builder_field.setter_ident)
+        {
+            return Err(Error::new_spanned(
+                &builder_field.setter_ident,
+                "`builder(try_setter)` requires a setter for the whole field; \
+                 use an `each` name different from the field name",
+            ));
+        }
+        // `each`は、要素の型がわかるコレクション型のフィールドにのみ指定できる
+        if builder_field.each.is_some()
+            && !matches!(builder_field.field_type(), FieldType::Collection(_))
+        {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "`builder(each = \"...\")` requires a collection type; \
+                 use `builder(each(name = \"...\", item = \"...\"))` for custom collections",
+            ));
+        }
+        if struct_attrs.constant && !builder_field.has_const_fallback() {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "`builder(const)` requires a constant default; use `builder(default = \"...\")`",
+            ));
+        }
+        if let Some(merge) = &field_attrs.merge {
+            if builder_field.merge == MergeStrategy::Append
+                && !matches!(builder_field.field_type(), FieldType::Collection(_))
+            {
+                return Err(Error::new_spanned(
+                    merge,
+                    "`builder(merge = \"append\")` requires a collection type",
+                ));
+            }
+        }
+    }
+    // ビルダーに同じ名前のメソッドを作成しないように、メソッドの名前の重複を検出
+    let mut method_idents = vec![
+        struct_attrs.build_ident(),
+        format_ident!("is_set"),
+        format_ident!("missing_fields"),
+    ];
+    if !struct_attrs.typestate {
+        method_idents.push(format_ident!("merge"));
+    }
+    for f in &builder_fields {
+        for ident in f.method_idents() {
+            if method_idents.contains(&ident) {
+                return Err(Error::new_spanned(
+                    &ident,
+                    format!("builder method `{}` is generated more than once", ident),
+                ));
+            }
+            method_idents.push(ident);
+        }
+    }
+
+    Ok((builder_fields, skipped_fields))
+}
+
+/// トークン列に含まれる`Self`を探す。
+fn find_self_ty(tokens: TokenStream2) -> Option<Ident> {
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Ident(ident) if ident == "Self" => Some(ident),
+        TokenTree::Group(group) => find_self_ty(group.stream()),
+        _ => None,
+    })
+}
+
+/// `builder`属性を付与した関数の引数を設定して、関数を呼び出すビルダーを作成する。
+///
+/// `fn connect(host: &str, port: u16)`の場合は、`connect_builder()`で`ConnectBuilder`を
+/// 作成し、`connect_builder().host("...").port(80).call()`のように関数を呼び出す。
+/// 引数は構造体のフィールドと同様に扱うため、`Option`型の引数は省略でき、引数に付与した
+/// `builder`属性で`each`や`default`などを指定できる。必須の引数を設定し忘れた場合は
+/// コンパイルエラーになるように、ビルダーは型状態を持つ。
+///
+/// 引数の型で省略されたライフタイムは、ビルダーのライフタイムパラメーター`'__builder`に
+/// 置き換える。
+fn impl_fn_builder(args: TokenStream2, mut item: ItemFn) -> Result<TokenStream2> {
+    // 関数に付与した属性の内容は、構造体に付与した`builder`属性と同様に解析する
+    let attr: Attribute = parse_quote! { #[builder(typestate, #args)] };
+    let mut struct_attrs = inspect_struct_attrs(std::slice::from_ref(&attr))?;
+    if let Some(path) = &struct_attrs.default {
+        return Err(Error::new_spanned(
+            path,
+            "`builder(default)` is not supported on functions",
+        ));
+    }
+    if let Some(validate) = &struct_attrs.validate {
+        return Err(Error::new_spanned(
+            validate,
+            "`builder(validate = \"...\")` is not supported on functions; \
+             use it on the parameters instead",
+        ));
+    }
+    struct_attrs.pattern = BuilderPattern::Owned;
+    struct_attrs
+        .build_fn
+        .get_or_insert_with(|| format_ident!("call"));
+
+    // `impl`ブロックの中にはビルダーの型を定義できないため、関連関数には使用できない
+    if let Some(self_ty) = find_self_ty(item.sig.to_token_stream()) {
+        return Err(Error::new(
+            self_ty.span(),
+            "`builder` does not support associated functions; use it on free functions",
+        ));
+    }
+
+    // 引数をフィールドとして扱い、引数に付与した`builder`属性は関数から取り除く
+    let mut elided = ElidedLifetimes::default();
+    let mut params = vec![];
+    let mut named = Punctuated::<Field, Token![,]>::new();
+    for input in &mut item.sig.inputs {
+        let FnArg::Typed(pat_type) = input else {
+            return Err(Error::new_spanned(
+                input,
+                "`builder` does not support methods; use it on free functions",
+            ));
+        };
+        let Pat::Ident(pat_ident) = &*pat_type.pat else {
+            return Err(Error::new_spanned(
+                &pat_type.pat,
+                "`builder` requires each parameter to be a plain identifier",
             ));
+        };
+        let mut ty = (*pat_type.ty).clone();
+        elided.visit_type_mut(&mut ty);
+        let (attrs, other_attrs) = pat_type
+            .attrs
+            .drain(..)
+            .partition(|attr| attr.path().is_ident("builder"));
+        pat_type.attrs = other_attrs;
+        params.push(pat_ident.ident.clone());
+        named.push(Field {
+            attrs,
+            vis: Visibility::Inherited,
+            mutability: FieldMutability::None,
+            ident: Some(pat_ident.ident.clone()),
+            colon_token: Some(Default::default()),
+            ty,
+        });
+    }
+    if let Some(impl_trait) = &elided.impl_trait {
+        return Err(Error::new_spanned(
+            impl_trait,
+            "`impl Trait` parameters are not supported by `builder`; use a generic parameter",
+        ));
+    }
+    let fields = Fields::Named(FieldsNamed {
+        brace_token: Default::default(),
+        named,
+    });
+    let (fields, skipped_fields) =
+        builder_fields(&fields, &struct_attrs, SerdeRenameAll::default())?;
+    let std_lib = struct_attrs.std_lib(&fields);
+    let mut output = match &item.sig.output {
+        ReturnType::Default => parse_quote! { () },
+        ReturnType::Type(_, ty) => (**ty).clone(),
+    };
+    elided.visit_type_mut(&mut output);
+
+    // 関数を呼び出すときは、型引数及び定数引数のみを指定する
+    let fn_ident = &item.sig.ident;
+    let fn_generics = &item.sig.generics;
+    let type_args = fn_generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Lifetime(_) => None,
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+        })
+        .collect::<Vec<_>>();
+    let turbofish = if type_args.is_empty() {
+        quote! {}
+    } else {
+        quote! { ::<#(#type_args),*> }
+    };
+    let mut generics = fn_generics.clone();
+    if elided.found {
+        generics.params.insert(0, parse_quote! { '__builder });
+    }
+    let target = BuilderTarget {
+        builder_ident: struct_attrs.name.clone().unwrap_or_else(|| {
+            format_ident!("{}Builder", to_camel_case(&fn_ident.unraw().to_string()))
+        }),
+        vis: struct_attrs.vis.clone().unwrap_or_else(|| item.vis.clone()),
+        constructor_ident: struct_attrs
+            .constructor
+            .clone()
+            .unwrap_or_else(|| format_ident!("{}_builder", fn_ident)),
+        path: quote! { #fn_ident },
+        fields,
+        skipped_fields,
+        struct_default: false,
+        kind: TargetKind::Function(Box::new(FunctionTarget {
+            params,
+            turbofish,
+            output,
+            asyncness: item.sig.asyncness,
+            unsafety: item.sig.unsafety,
+        })),
+        std_lib,
+    };
+    let builder = impl_typestate_builder(fn_ident, &generics, &struct_attrs, &target);
+
+    Ok(quote! {
+        #item
+
+        #builder
+    })
+}
+
+/// 型で省略されたライフタイムを、ビルダーのライフタイムパラメーターに置き換える。
+///
+/// 関数ポインタ及び`Fn(&str)`のような型の引数は、それ自体がライフタイムの省略の範囲に
+/// なるため置き換えない。
+#[derive(Default)]
+struct ElidedLifetimes {
+    /// 省略されたライフタイムを置き換えた場合は`true`。
+    found: bool,
+    /// 最初に見つかった`impl Trait`の型。
+    impl_trait: Option<TypeImplTrait>,
+}
+
+impl ElidedLifetimes {
+    /// 省略されたライフタイムを置き換えるライフタイム。
+    fn lifetime(&mut self) -> Lifetime {
+        self.found = true;
+        Lifetime::new("'__builder", Span::call_site())
+    }
+}
+
+impl VisitMut for ElidedLifetimes {
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.lifetime());
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime();
+        }
+    }
+
+    fn visit_type_impl_trait_mut(&mut self, impl_trait: &mut TypeImplTrait) {
+        self.impl_trait.get_or_insert_with(|| impl_trait.clone());
+        visit_mut::visit_type_impl_trait_mut(self, impl_trait);
+    }
+
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(&mut self, _: &mut ParenthesizedGenericArguments) {
+    }
+}
+
+/// ビルダーを作成する対象。
+struct BuilderTarget {
+    /// ビルダーの名前。
+    builder_ident: Ident,
+    /// ビルダー、そのメソッド及びビルダーを作成する関数の可視性。
+    vis: Visibility,
+    /// ビルダーを作成する関数の名前。
+    constructor_ident: Ident,
+    /// 値を構築するときに指定する構造体またはヴァリアントのパス、または呼び出す関数の
+    /// パス。
+    path: TokenStream2,
+    /// ビルダーが値を設定するフィールド。
+    fields: Vec<BuilderField>,
+    /// `builder(skip)`属性でビルダーに含めないフィールド。
+    skipped_fields: Vec<SkippedField>,
+    /// 構造体の`Default`実装が返す値から構築する場合は`true`。
+    struct_default: bool,
+    /// ビルダーを作成する対象の種類。
+    kind: TargetKind,
+    /// 生成したコードが使用する標準ライブラリのクレート。
+    std_lib: StdLib,
+}
+
+/// ビルダーを作成する対象の種類。
+enum TargetKind {
+    /// 構造体。
+    Struct,
+    /// 列挙型のヴァリアント。
+    Variant,
+    /// `builder`属性を付与した関数。buildメソッドは関数を呼び出す。
+    Function(Box<FunctionTarget>),
+}
+
+/// `builder`属性を付与した関数を呼び出すための情報。
+struct FunctionTarget {
+    /// 関数の引数の名前。関数を呼び出すときは、この順番で値を渡す。
+    params: Vec<Ident>,
+    /// 関数を呼び出すときに指定する型引数及び定数引数。
+    turbofish: TokenStream2,
+    /// 関数の戻り値の型。
+    output: Type,
+    /// `async`関数の場合は`Some`。
+    asyncness: Option<Token![async]>,
+    /// `unsafe`関数の場合は`Some`。
+    unsafety: Option<Token![unsafe]>,
+}
+
+impl BuilderTarget {
+    /// 値を設定しないとビルドできないフィールドを返す。
+    fn required_fields(&self) -> Vec<&BuilderField> {
+        if self.struct_default {
+            return vec![];
+        }
+        self.fields.iter().filter(|f| f.is_required()).collect()
+    }
+
+    /// buildメソッドで値を構築する式を作成する。
+    ///
+    /// `stored`は、ビルダーのフィールドに格納された`Option`を取り出す式を返す関数である。
+    /// 構造体の`Default`実装から構築する場合は、その値をもとに、ビルダーに値が設定された
+    /// フィールドと、デフォルト値が指定されたフィールドを上書きする。
+    /// `builder(skip)`属性でビルダーに含めないフィールドには、指定された値、または
+    /// フィールドの型の`Default`実装が返す値を設定する。
+    ///
+    /// 検証関数が指定されている場合は、フィールドの値及び構築した値を検証して、検証に
+    /// 失敗した場合は`?`演算子でエラーを返す。フィールドの値は、ビルダーから取り出す前に
+    /// 検証するため、検証に失敗しても`mutable`パターンのビルダーの値は失われない。
+    ///
+    /// `builder(sub_builder)`が指定されたフィールドは、内側のビルダーでフィールドの値を
+    /// 構築する。`error_ident`は、内側のビルダーのエラーを変換して返すエラー型である。
+    fn build_value(
+        &self,
+        ident: &Ident,
+        ty_generics: &TypeGenerics,
+        validate: Option<&Expr>,
+        error_ident: &Ident,
+        serde: bool,
+        stored: impl Fn(&Ident) -> TokenStream2,
+    ) -> TokenStream2 {
+        let path = &self.path;
+        // 検証に失敗した場合にビルダーの値が失われないように、値を取り出す前にビルダーに
+        // 格納された値を借用して検証する
+        let validated = |f: &&BuilderField| f.validate.is_some() && f.sub_builder.is_none();
+        let built = if self.struct_default {
+            // 値が設定されていない場合は、デフォルト値または構造体の`Default`実装が返す値を
+            // 検証する
+            let validations = self.fields.iter().filter(validated).map(|f| {
+                let (ident, member) = (&f.ident, &f.member);
+                let validate = &f.validate;
+                let default = f.default.as_ref().map(|default| {
+                    quote! { built.#member = #default; }
+                });
+                quote! {
+                    match &self.#ident {
+                        ::core::option::Option::Some(value) => #validate(value)?,
+                        ::core::option::Option::None => {
+                            #default
+                            #validate(&built.#member)?;
+                        }
+                    }
+                }
+            });
+            let assignments = self.fields.iter().map(|f| {
+                let (member, stored) = (&f.member, stored(&f.ident));
+                match &f.default {
+                    // 内側のビルダーが設定されていない場合は、構造体の`Default`実装が返す
+                    // 値のままにする
+                    _ if f.sub_builder.is_some() => {
+                        let sub_build = sub_build(f, error_ident, serde, self.std_lib);
+                        let validation = f.validate.as_ref().map(|validate| {
+                            quote! { #validate(&built.#member)?; }
+                        });
+                        quote! {
+                            if let ::core::option::Option::Some(builder) = #stored {
+                                built.#member = #sub_build;
+                            }
+                            #validation
+                        }
+                    }
+                    // 検証するときにデフォルト値を設定しているため、ビルダーの値だけを設定する
+                    Some(default) if f.validate.is_none() => quote! {
+                        built.#member = #stored.unwrap_or_else(|| #default);
+                    },
+                    _ => quote! {
+                        if let ::core::option::Option::Some(value) = #stored {
+                            built.#member = value;
+                        }
+                    },
+                }
+            });
+            // `builder(skip = "...")`で値が指定されていないフィールドは、構造体の
+            // `Default`実装が返す値のままにする
+            let skipped = self.skipped_fields.iter().filter_map(|f| match &f.value {
+                FieldDefault::Trait => None,
+                FieldDefault::Expr(expr) => {
+                    let member = &f.member;
+                    Some(quote! { built.#member = #expr; })
+                }
+            });
+            quote! {
+                let mut built: #ident #ty_generics = ::core::default::Default::default();
+                #(#validations)*
+                #(#assignments)*
+                #(#skipped)*
+            }
+        } else {
+            // 値が設定されていない場合に使用する値は、検証してから変数に格納しておく
+            let validations = self.fields.iter().filter(validated).map(|f| {
+                let ident = &f.ident;
+                let validate = &f.validate;
+                match f.fallback() {
+                    Some(fallback) => {
+                        let fallback_ident = format_ident!("__fallback_{}", ident.unraw());
+                        quote! {
+                            let #fallback_ident = match &self.#ident {
+                                ::core::option::Option::Some(value) => {
+                                    #validate(value)?;
+                                    ::core::option::Option::None
+                                }
+                                ::core::option::Option::None => {
+                                    let value = #fallback;
+                                    #validate(&value)?;
+                                    ::core::option::Option::Some(value)
+                                }
+                            };
+                        }
+                    }
+                    None => quote! {
+                        if let ::core::option::Option::Some(value) = &self.#ident {
+                            #validate(value)?;
+                        }
+                    },
+                }
+            });
+            // フィールドの値をフィールドと同じ名前の変数に取り出してから構築
+            let values = self.fields.iter().map(|f| {
+                let (ident, stored) = (&f.ident, stored(&f.ident));
+                match f.fallback() {
+                    _ if f.sub_builder.is_some() => {
+                        let sub_build = sub_build(f, error_ident, serde, self.std_lib);
+                        let validation = f.validate.as_ref().map(|validate| {
+                            quote! { #validate(&#ident)?; }
+                        });
+                        quote! {
+                            let #ident = {
+                                let builder = #stored.unwrap_or_default();
+                                #sub_build
+                            };
+                            #validation
+                        }
+                    }
+                    Some(_) if f.validate.is_some() => {
+                        let fallback_ident = format_ident!("__fallback_{}", ident.unraw());
+                        quote! {
+                            let #ident = match #fallback_ident {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => #stored.unwrap(),
+                            };
+                        }
+                    }
+                    Some(fallback) => quote! {
+                        let #ident = #stored.unwrap_or_else(|| #fallback);
+                    },
+                    None => quote! {
+                        let #ident = #stored.unwrap();
+                    },
+                }
+            });
+            let built = match &self.kind {
+                TargetKind::Function(function) => self.call_function(function),
+                TargetKind::Struct | TargetKind::Variant => {
+                    let members = self.fields.iter().map(|f| &f.member);
+                    let field_idents = self.fields.iter().map(|f| &f.ident);
+                    let skipped_members = self.skipped_fields.iter().map(|f| &f.member);
+                    let skipped_values = self.skipped_fields.iter().map(|f| &f.value);
+                    quote! {
+                        let built = #path {
+                            #(#members: #field_idents,)*
+                            #(#skipped_members: #skipped_values,)*
+                        };
+                    }
+                }
+            };
+            quote! {
+                #(#validations)*
+                #(#values)*
+                #built
+            }
+        };
+        let validation = validate.map(|validate| quote! { #validate(&built)?; });
+
+        quote! {
+            {
+                #built
+                #validation
+                built
+            }
+        }
+    }
+
+    /// フィールドの値を取り出した変数を引数として、関数を呼び出した結果を`built`に格納する
+    /// 文を作成する。
+    ///
+    /// `builder(skip)`属性が付与された引数には、指定された値、または引数の型の`Default`
+    /// 実装が返す値を渡す。
+    fn call_function(&self, function: &FunctionTarget) -> TokenStream2 {
+        let path = &self.path;
+        let FunctionTarget {
+            turbofish,
+            asyncness,
+            unsafety,
+            ..
+        } = function;
+        let args = function.params.iter().map(|param| {
+            let member = Member::Named(param.clone());
+            match self.fields.iter().find(|f| f.member == member) {
+                Some(f) => f.ident.to_token_stream(),
+                None => self
+                    .skipped_fields
+                    .iter()
+                    .find(|f| f.member == member)
+                    .map(|f| f.value.to_token_stream())
+                    .unwrap_or_default(),
+            }
+        });
+        let call = quote! { #path #turbofish(#(#args),*) };
+        let call = match asyncness {
+            Some(_) => quote! { #call.await },
+            None => call,
+        };
+        match unsafety {
+            Some(_) => quote! {
+                #[allow(unused_unsafe)]
+                let built = unsafe { #call };
+            },
+            None => quote! { let built = #call; },
+        }
+    }
+
+    /// ビルダーの`PhantomData`に指定する、構築する値の型を表現する型を返す。
+    ///
+    /// 関数の引数のビルダーは、すべてのライフタイム及び型パラメーターを使用するために、
+    /// それらを要素とするタプルを返す関数ポインタの型を使用する。
+    fn marker_ty(&self, ident: &Ident, generics: &Generics) -> TokenStream2 {
+        let (_, ty_generics, _) = generics.split_for_impl();
+        match &self.kind {
+            TargetKind::Function(_) => {
+                let lifetimes = generics.lifetimes().map(|param| &param.lifetime);
+                let type_params = generics.type_params().map(|param| &param.ident);
+                quote! { fn() -> (#(&#lifetimes (),)* #(#type_params,)*) }
+            }
+            TargetKind::Struct | TargetKind::Variant => quote! { fn() -> #ident #ty_generics },
+        }
+    }
+
+    /// buildメソッドが返す値の型を返す。
+    fn output_ty(&self, ident: &Ident, ty_generics: &TypeGenerics) -> TokenStream2 {
+        match &self.kind {
+            TargetKind::Function(function) => function.output.to_token_stream(),
+            TargetKind::Struct | TargetKind::Variant => quote! { #ident #ty_generics },
+        }
+    }
+
+    /// 構造体の値から、その値のすべてのフィールドを設定したビルダーを作成する`From`の
+    /// 実装と、`to_builder`メソッドを作成する。
+    ///
+    /// 列挙型の値は他のヴァリアントの場合があるため、ヴァリアントのビルダーには作成しない。
+    /// 関数の引数のビルダーにも作成しない。
+    /// `to_builder`メソッドは構造体が`Clone`を実装している場合のみ呼び出せる。
+    fn impl_from_value(
+        &self,
+        ident: &Ident,
+        generics: &Generics,
+        builder_ty: &TokenStream2,
+    ) -> TokenStream2 {
+        if !matches!(self.kind, TargetKind::Struct) {
+            return quote! {};
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let (builder_ident, vis) = (&self.builder_ident, &self.vis);
+        let field_idents = self.fields.iter().map(|f| &f.ident);
+        // 内側のビルダーを格納するフィールドには、フィールドの値から作成したビルダーを設定
+        let values = self.fields.iter().map(|f| {
+            let member = &f.member;
+            if f.sub_builder.is_some() {
+                quote! { ::core::convert::From::from(value.#member) }
+            } else {
+                quote! { value.#member }
+            }
+        });
+
+        quote! {
+            impl #impl_generics ::core::convert::From<#ident #ty_generics> for #builder_ty
+                #where_clause
+            {
+                fn from(value: #ident #ty_generics) -> Self {
+                    #builder_ident {
+                        #(#field_idents: ::core::option::Option::Some(#values),)*
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                // `Clone`を実装していない構造体でもエラーにならないように、境界を
+                // 高階トレイト境界にして、呼び出すときまで確認を遅らせる
+                #vis fn to_builder(&self) -> #builder_ty
+                where
+                    for<'__builder> #ident #ty_generics: ::core::clone::Clone,
+                {
+                    ::core::convert::From::from(::core::clone::Clone::clone(self))
+                }
+            }
+        }
+    }
+
+    /// フィールドまたは構築した値を検証する関数が指定されている場合は`true`を返す。
+    fn has_validation(&self, validate: Option<&Expr>) -> bool {
+        validate.is_some() || self.fields.iter().any(|f| f.validate.is_some())
+    }
+
+    /// 値が設定されていない必須フィールドの名前を、フィールドの順に`report`に渡す文を
+    /// 作成する。
+    ///
+    /// `builder(sub_builder)`が指定されたフィールドは、内側のビルダーに値が設定されて
+    /// いないフィールドを`tls.cert_path`のようなパスで渡す。内側のビルダーが設定されて
+    /// いない場合は、構造体の`Default`実装が返す値を使用するときを除き、値が設定されて
+    /// いないビルダーで構築するため、そのビルダーのフィールドを渡す。
+    fn report_missing(&self, serde: bool) -> TokenStream2 {
+        let required_fields = self.required_fields();
+        let alloc = self.std_lib.alloc_crate();
+        let reports = self.fields.iter().filter_map(|f| {
+            let (ident, name) = (&f.ident, f.report_name(serde));
+            let Some(sub_builder) = &f.sub_builder else {
+                return required_fields
+                    .iter()
+                    .any(|required| required.ident == f.ident)
+                    .then(|| {
+                        quote! {
+                            if self.#ident.is_none() {
+                                report(#name);
+                            }
+                        }
+                    });
+            };
+            let report = quote! {
+                builder.__missing_paths(&mut |path: &str| {
+                    report(&#alloc::format!("{}.{}", #name, path));
+                });
+            };
+            Some(if self.struct_default {
+                quote! {
+                    if let ::core::option::Option::Some(builder) = &self.#ident {
+                        #report
+                    }
+                }
+            } else {
+                quote! {
+                    match &self.#ident {
+                        ::core::option::Option::Some(builder) => {
+                            #report
+                        }
+                        ::core::option::Option::None => {
+                            let builder = <#sub_builder as ::core::default::Default>::default();
+                            #report
+                        }
+                    }
+                }
+            })
+        });
+
+        quote! { #(#reports)* }
+    }
+
+    /// ビルダーのフィールドの情報を表現する型と、ビルダーに実装するフィールドの情報を
+    /// 返す定数及びメソッドを作成する。
+    ///
+    /// `FIELDS`はフィールドの名前、型、種類、デフォルト値の有無及びドキュメントコメントを
+    /// 返す。`is_set`メソッドは名前で指定したフィールドに値が設定されているかを返し、
+    /// `missing_fields`メソッドは値が設定されていない必須フィールドの名前を返す。
+    /// `builder(serde)`が指定された場合、フィールドの名前はserdeでデシリアライズする
+    /// ときの名前であり、`is_set`メソッドはビルダーのフィールドの名前も受け付ける。
+    ///
+    /// 型、`FIELDS`定数及びメソッドを返す。
+    fn impl_field_info(&self, serde: bool) -> (TokenStream2, TokenStream2, TokenStream2) {
+        let (builder_ident, vis) = (&self.builder_ident, &self.vis);
+        let info_ident = format_ident!("{}FieldInfo", builder_ident);
+        let kind_ident = format_ident!("{}FieldKind", builder_ident);
+        let required_fields = self.required_fields();
+        let infos = self.fields.iter().map(|f| {
+            let name = f.report_name(serde);
+            let ty = type_name(&f.ty);
+            let kind = if required_fields
+                .iter()
+                .any(|required| required.ident == f.ident)
+            {
+                quote! { Required }
+            } else if f.sub_builder.is_some() {
+                quote! { Nested }
+            } else if matches!(f.field_type(), FieldType::Collection(_)) {
+                quote! { Collection }
+            } else {
+                quote! { Optional }
+            };
+            let has_default = f.default.is_some() || self.struct_default;
+            let doc = &f.doc;
+            quote! {
+                #info_ident {
+                    name: #name,
+                    ty: #ty,
+                    kind: #kind_ident::#kind,
+                    has_default: #has_default,
+                    doc: #doc,
+                }
+            }
+        });
+        // `is_set`はserdeで読み書きするときの名前に加えて、ビルダーのフィールドの名前も
+        // 受け付ける。ほかのフィールドの名前と重なる名前は除く
+        let mut used: Vec<String> = self.fields.iter().map(|f| f.report_name(serde)).collect();
+        let names = self
+            .fields
+            .iter()
+            .map(|f| {
+                let mut names = vec![f.report_name(serde)];
+                let ident = f.ident.unraw().to_string();
+                if !used.contains(&ident) {
+                    used.push(ident.clone());
+                    names.push(ident);
+                }
+                names
+            })
+            .collect::<Vec<_>>();
+        let names = names.iter().map(|names| quote! { #(#names)|* });
+        let field_idents = self.fields.iter().map(|f| &f.ident);
+        // アロケーターを使用しない場合は、必須フィールドの数の配列と名前の数を返す
+        let required_idents = required_fields.iter().map(|f| &f.ident);
+        let required_names = required_fields.iter().map(|f| f.report_name(serde));
+        let missing_fields = match self.std_lib {
+            StdLib::Core => {
+                let len = required_fields.len();
+                quote! {
+                    /// 値が設定されていない必須フィールドの名前を、配列の先頭から詰めて
+                    /// 名前の数とともに返す。
+                    #vis fn missing_fields(&self) -> ([&'static str; #len], usize) {
+                        #[allow(unused_mut)]
+                        let mut missing: [&'static str; #len] = [""; #len];
+                        #[allow(unused_mut)]
+                        let mut len = 0;
+                        #(
+                            if self.#required_idents.is_none() {
+                                missing[len] = #required_names;
+                                len += 1;
+                            }
+                        )*
+                        (missing, len)
+                    }
+                }
+            }
+            std_lib => {
+                let alloc = std_lib.alloc_crate();
+                let report_missing = self.report_missing(serde);
+                quote! {
+                    /// 値が設定されていない必須フィールドの名前を返す。
+                    ///
+                    /// 内側のビルダーに値が設定されていないフィールドは、`tls.cert_path`の
+                    /// ようなパスで返す。
+                    #vis fn missing_fields(&self) -> #alloc::vec::Vec<#alloc::string::String> {
+                        let mut missing = #alloc::vec::Vec::new();
+                        #[allow(unused_variables)]
+                        let report = &mut |path: &str| {
+                            missing.push(#alloc::borrow::ToOwned::to_owned(path));
+                        };
+                        #report_missing
+                        missing
+                    }
+                }
+            }
+        };
+
+        let types = quote! {
+            /// ビルダーのフィールドの情報。
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis struct #info_ident {
+                /// フィールドの名前。
+                pub name: &'static str,
+                /// フィールドの型。
+                pub ty: &'static str,
+                /// フィールドの種類。
+                pub kind: #kind_ident,
+                /// デフォルト値が指定されている場合は`true`。
+                pub has_default: bool,
+                /// フィールドのドキュメントコメント。
+                pub doc: &'static str,
+            }
+
+            /// ビルダーのフィールドの種類。
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis enum #kind_ident {
+                /// 値を設定しないとビルドできないフィールド。
+                Required,
+                /// 値の設定が任意のフィールド。
+                Optional,
+                /// 要素を追加できるコレクション型のフィールド。
+                Collection,
+                /// 内側のビルダーで値を構築するフィールド。
+                Nested,
+            }
+        };
+        let fields_const = quote! {
+            /// ビルダーのフィールドの情報。
+            #vis const FIELDS: &'static [#info_ident] = &[#(#infos),*];
+        };
+        let methods = quote! {
+            /// 名前で指定したフィールドに値が設定されている場合は`true`を返す。
+            ///
+            /// 名前には、`FIELDS`が返す名前とビルダーのフィールドの名前のどちらも指定できる。
+            #vis fn is_set(&self, name: &str) -> bool {
+                match name {
+                    #(#names => self.#field_idents.is_some(),)*
+                    _ => false,
+                }
+            }
+
+            #missing_fields
+        };
+
+        (types, fields_const, methods)
+    }
+}
+
+/// `builder(sub_builder)`が指定されたフィールドについて、変数`builder`に格納された
+/// 内側のビルダーで値を構築する式を作成する。
+///
+/// 内側のビルダーは、このマクロで生成した実行時に確認するビルダーである必要がある。
+/// buildメソッドの名前やエラー型の指定によらずに構築できるように、内側のビルダーに
+/// 生成した`__sub_build`メソッドを呼び出す。内側のビルダーに値が設定されていない
+/// フィールドは、値を取り出す前に外側のビルダーのbuildメソッドで報告するため、ここでは
+/// 内側のビルダーの検証に失敗した場合のエラーだけを変換する。
+fn sub_build(
+    field: &BuilderField,
+    error_ident: &Ident,
+    serde: bool,
+    std_lib: StdLib,
+) -> TokenStream2 {
+    let name = field.report_name(serde);
+    let alloc = std_lib.alloc_crate();
+    quote! {
+        {
+            #[allow(unused_mut)]
+            let mut builder = builder;
+            match builder.__sub_build() {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(err) => {
+                    return ::core::result::Result::Err(::core::convert::From::from(
+                        #error_ident::ValidationFailed(#alloc::format!("{}: {}", #name, err)),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// ビルダーを作成する対象の構造体のフィールド。
+struct BuilderField {
+    /// ビルダーのフィールドの名前。
+    ident: Ident,
+    /// セッターの名前。`builder(setter(prefix = "..."))`で指定された接頭辞を付与する。
+    setter_ident: Ident,
+    /// 構造体のフィールド名、またはタプル構造体のフィールドの位置。
+    member: Member,
+    /// フィールドの型。
+    ty: Type,
+    /// `builder(each = "...")`属性で指定されたメソッド名。
+    each: Option<Ident>,
+    /// `builder(each(item = "..."))`属性で指定された、コレクションの要素の型。
+    each_item: Option<Type>,
+    /// `builder(default)`属性で指定されたデフォルト値。
+    default: Option<FieldDefault>,
+    /// `builder(optional)`が指定された場合は`Some(true)`、`builder(required)`が
+    /// 指定された場合は`Some(false)`。
+    optional: Option<bool>,
+    /// `builder(private)`が指定されている場合は`true`。
+    private: bool,
+    /// `builder(try_setter)`が指定されている場合は`true`。
+    try_setter: bool,
+    /// serdeでフィールドを読み書きするときの名前。
+    serde_names: SerdeNames,
+    /// `builder(serde)`が指定された場合に、ビルダーのフィールドにも付与する`serde`属性の
+    /// 内容。
+    serde_attrs: Vec<TokenStream2>,
+    /// `builder(merge = "...")`属性で指定された、ビルダーをマージするときの方法。
+    merge: MergeStrategy,
+    /// `builder(sub_builder)`属性が指定された場合の、フィールドの値を構築する内側の
+    /// ビルダーの型。
+    sub_builder: Option<Type>,
+    /// `builder(validate = "...")`属性で指定された、フィールドの値を検証する関数。
+    validate: Option<Expr>,
+    /// セッターが`impl Into<T>`を受け取る場合は`true`。
+    into: bool,
+    /// `Option<T>`型のフィールドのセッターが`T`を受け取る場合は`true`。
+    strip_option: bool,
+    /// フィールドに付与されたドキュメントコメント。
+    doc: String,
+}
+
+impl BuilderField {
+    /// ビルダーのフィールドに`Option`でラップして格納する値の型を返す。
+    ///
+    /// `builder(sub_builder)`が指定されたフィールドには、フィールドの値ではなく内側の
+    /// ビルダーを格納する。
+    fn slot_ty(&self) -> &Type {
+        self.sub_builder.as_ref().unwrap_or(&self.ty)
+    }
+
+    /// フィールドの型の分類を返す。
+    ///
+    /// `builder(each(item = "..."))`で要素の型が指定されている場合は、`Extend`及び
+    /// `Default`を実装する任意のコレクション型として扱う。
+    fn field_type(&self) -> FieldType<'_> {
+        match &self.each_item {
+            Some(item) => FieldType::Collection(CollectionItem::Value(item)),
+            None => determine_field_type(&self.ty),
+        }
+    }
+
+    /// セッターの引数の型と、その引数からビルダーのフィールドに格納する値を作成する式を
+    /// 返す。
+    ///
+    /// `Option<T>`型のフィールドのセッターは、`strip_option`が`true`の場合は`T`を受け取り、
+    /// `Some`でラップした値を格納する。`into`が`true`の場合、セッターは`impl Into<T>`を
+    /// 受け取り、`Into::into`で変換した値を格納する。
+    fn setter_arg(&self) -> (TokenStream2, TokenStream2) {
+        let ident = &self.ident;
+        let (arg_ty, wrap_some) = self.setter_ty();
+        let (arg_ty, value) = if self.into {
+            (
+                quote! { impl ::core::convert::Into<#arg_ty> },
+                quote! { ::core::convert::Into::into(#ident) },
+            )
+        } else {
+            (quote! { #arg_ty }, quote! { #ident })
+        };
+        if wrap_some {
+            (arg_ty, quote! { ::core::option::Option::Some(#value) })
+        } else {
+            (arg_ty, value)
+        }
+    }
+
+    /// セッターが受け取る値の型と、その値を`Some`でラップしてから格納する場合は`true`を
+    /// 返す。
+    fn setter_ty(&self) -> (&Type, bool) {
+        match self.field_type() {
+            FieldType::Option(inner_type) if self.strip_option => (inner_type, true),
+            _ => (&self.ty, false),
+        }
+    }
+
+    /// `builder(try_setter)`が指定されている場合は、`TryInto`で変換した値を設定する
+    /// メソッドの名前を返す。
+    fn try_setter_ident(&self) -> Option<Ident> {
+        self.try_setter
+            .then(|| format_ident!("try_{}", self.setter_ident))
+    }
+
+    /// 値が設定されていないフィールドを報告するときの名前を返す。
+    ///
+    /// `builder(serde)`が指定された場合は、serdeでデシリアライズするときの名前を使用する。
+    fn report_name(&self, serde: bool) -> String {
+        if serde {
+            self.serde_names.deserialize.clone()
+        } else {
+            self.ident.unraw().to_string()
+        }
+    }
+
+    /// 複数の要素を追加するメソッドの名前を返す。
+    fn extend_ident(&self) -> Ident {
+        format_ident!("extend_{}", self.ident)
+    }
+
+    /// フィールドに値を設定するためにビルダーに作成するメソッドの名前を返す。
+    fn method_idents(&self) -> Vec<Ident> {
+        let mut idents = match &self.each {
+            Some(each) if each == &self.setter_ident => vec![each.clone(), self.extend_ident()],
+            Some(each) => vec![self.setter_ident.clone(), each.clone(), self.extend_ident()],
+            None => vec![self.setter_ident.clone()],
+        };
+        idents.extend(self.try_setter_ident());
+        idents
+    }
+
+    /// フィールドに値を設定するメソッドの可視性を返す。
+    ///
+    /// `builder(private)`が指定されている場合、メソッドはビルダーと同じモジュールからのみ
+    /// 呼び出せる。
+    fn setter_vis(&self, vis: &Visibility) -> Visibility {
+        if self.private {
+            Visibility::Inherited
+        } else {
+            vis.clone()
+        }
+    }
+
+    /// フィールドに値を設定しないとビルドできない場合は`true`を返す。
+    ///
+    /// 内側のビルダーを格納するフィールドは、内側のビルダーの必須フィールドをビルド時に
+    /// 確認するため、必須フィールドとして扱わない。
+    fn is_required(&self) -> bool {
+        self.sub_builder.is_none() && self.fallback().is_none()
+    }
+
+    /// 値が設定されていない場合に、定数式で値を決められる場合は`true`を返す。
+    ///
+    /// 必須フィールド、`Option`型のフィールド、及び`builder(default = "...")`で
+    /// デフォルト値が指定されたフィールドが該当する。
+    fn has_const_fallback(&self) -> bool {
+        match &self.default {
+            Some(FieldDefault::Expr(_)) => true,
+            Some(FieldDefault::Trait) => false,
+            None => self.is_required() || matches!(self.field_type(), FieldType::Option(_)),
+        }
+    }
+
+    /// ビルダーのフィールドに値が設定されていない場合に使用する値を返す。
+    ///
+    /// デフォルト値が指定されていない場合、`Option`型のフィールドは`None`、コレクション型の
+    /// フィールドは空のコレクションを使用する。それ以外の型のフィールドは、値を設定しないと
+    /// ビルドできないため`None`を返す。
+    ///
+    /// `builder(optional)`または`builder(required)`が指定されている場合は、型の分類に
+    /// かかわらずその指定に従う。型の別名などで型を分類できない`optional`のフィールドは、
+    /// フィールドの型の`Default`実装を使用する。
+    fn fallback(&self) -> Option<TokenStream2> {
+        if let Some(default) = &self.default {
+            return Some(default.to_token_stream());
+        }
+        match (self.optional, self.field_type()) {
+            (Some(false), _) => None,
+            (Some(true), FieldType::Raw) => Some(quote! { ::core::default::Default::default() }),
+            (_, field_type) => field_type.fallback(),
+        }
+    }
+}
+
+/// `builder(skip)`属性でビルダーに含めないフィールド。
+struct SkippedField {
+    /// 構造体のフィールド名、またはタプル構造体のフィールドの位置。
+    member: Member,
+    /// buildメソッドでフィールドに設定する値。
+    value: FieldDefault,
+}
+
+/// serdeでフィールドを読み書きするときの名前。
+struct SerdeNames {
+    /// シリアライズするときの名前。
+    serialize: String,
+    /// デシリアライズするときの名前。
+    deserialize: String,
+}
+
+/// `serde(rename_all = "...")`属性で指定された、フィールド名の変換規則。
+#[derive(Clone, Copy, Default)]
+struct SerdeRenameAll {
+    /// シリアライズするときの規則。
+    serialize: Option<RenameRule>,
+    /// デシリアライズするときの規則。
+    deserialize: Option<RenameRule>,
+}
+
+impl SerdeRenameAll {
+    /// 規則が指定されていない場合に、`other`の規則を使用する。
+    fn or(self, other: Self) -> Self {
+        Self {
+            serialize: self.serialize.or(other.serialize),
+            deserialize: self.deserialize.or(other.deserialize),
+        }
+    }
+
+    /// フィールドをserdeで読み書きするときの名前を返す。
+    ///
+    /// `serde(rename = "...")`属性で名前が指定されていない場合は、構造体のフィールド名に
+    /// 変換規則を適用する。タプル構造体のフィールドの場合は、ビルダーのフィールドの名前を
+    /// そのまま使用する。
+    fn names(&self, attrs: &[Attribute], member: &Member, ident: &Ident) -> SerdeNames {
+        let (serialize, deserialize) = serde_attr_pair(attrs, "rename");
+        let name = |rename: Option<LitStr>, rule: Option<RenameRule>| match (rename, member) {
+            (Some(rename), _) => rename.value(),
+            (None, Member::Named(ident)) => {
+                let name = ident.unraw().to_string();
+                rule.map_or_else(|| name.clone(), |rule| rule.apply(&name))
+            }
+            (None, Member::Unnamed(_)) => ident.unraw().to_string(),
+        };
+        SerdeNames {
+            serialize: name(serialize, self.serialize),
+            deserialize: name(deserialize, self.deserialize),
+        }
+    }
+}
+
+/// serdeがフィールド名に適用する変換規則。
+#[derive(Clone, Copy)]
+enum RenameRule {
+    /// `lowercase`
+    Lower,
+    /// `UPPERCASE`
+    Upper,
+    /// `PascalCase`
+    Pascal,
+    /// `camelCase`
+    Camel,
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+    /// `kebab-case`
+    Kebab,
+    /// `SCREAMING-KEBAB-CASE`
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    /// serdeと同じ名前で指定された変換規則を解析する。
+    fn parse(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "lowercase" => Ok(Self::Lower),
+            "UPPERCASE" => Ok(Self::Upper),
+            "PascalCase" => Ok(Self::Pascal),
+            "camelCase" => Ok(Self::Camel),
+            "snake_case" => Ok(Self::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnake),
+            "kebab-case" => Ok(Self::Kebab),
+            "SCREAMING-KEBAB-CASE" => Ok(Self::ScreamingKebab),
+            _ => Err(Error::new_spanned(lit, "unknown serde rename rule")),
+        }
+    }
+
+    /// スネークケースのフィールド名に変換規則を適用する。
+    fn apply(self, name: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => name.to_owned(),
+            Self::Upper | Self::ScreamingSnake => name.to_ascii_uppercase(),
+            Self::Pascal => to_camel_case(name),
+            Self::Camel => {
+                let pascal = to_camel_case(name);
+                let mut chars = pascal.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_lowercase().chain(chars).collect()
+                })
+            }
+            Self::Kebab => name.replace('_', "-"),
+            Self::ScreamingKebab => name.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}
+
+/// ビルダーをマージするときに、マージするビルダーのフィールドに値が設定されている場合の
+/// 方法。
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MergeStrategy {
+    /// マージするビルダーの値で置き換える。
+    Replace,
+    /// マージするビルダーのコレクションの要素を追加する。
+    Append,
+    /// `builder(sub_builder)`が指定されたフィールドの場合、内側のビルダーをマージする。
+    Nested,
+}
+
+/// `builder(default)`属性で指定されたデフォルト値。
+enum FieldDefault {
+    /// `builder(default)`の場合、フィールドの型の`Default`実装が返す値。
+    Trait,
+    /// `builder(default = "...")`の場合、指定された式。
+    Expr(Expr),
+}
+
+impl ToTokens for FieldDefault {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        match self {
+            FieldDefault::Trait => tokens.extend(quote! { ::core::default::Default::default() }),
+            FieldDefault::Expr(expr) => expr.to_tokens(tokens),
+        }
+    }
+}
+
+/// フィールドに付与された`builder`属性の内容。
+#[derive(Default)]
+struct FieldAttrs {
+    /// `builder(each = "...")`または`builder(each(...))`で指定された、要素を追加する
+    /// メソッド。
+    each: Option<EachAttrs>,
+    /// `builder(name = "...")`または`builder(rename = "...")`で指定されたセッターの名前。
+    name: Option<Ident>,
+    /// `builder(default)`または`builder(default = "...")`で指定されたデフォルト値。
+    default: Option<FieldDefault>,
+    /// `builder(optional)`または`builder(required)`で指定された、値の設定が任意か。
+    optional: Option<bool>,
+    /// `builder(private)`が指定されている場合は`true`。
+    private: bool,
+    /// `builder(try_setter)`が指定されている場合は`true`。
+    try_setter: bool,
+    /// `builder(merge = "...")`で指定された、ビルダーをマージするときの方法。
+    merge: Option<LitStr>,
+    /// `builder(sub_builder)`が指定された場合は`Some(None)`、`builder(sub_builder = "...")`
+    /// でビルダーの型が指定された場合は`Some(Some(...))`。
+    sub_builder: Option<Option<Type>>,
+    /// `builder(skip)`または`builder(skip = "...")`で指定された、ビルダーに含めない
+    /// フィールドの値。
+    skip: Option<FieldDefault>,
+    /// `builder(validate = "...")`で指定された、フィールドの値を検証する関数。
+    validate: Option<Expr>,
+    /// `builder(setter(...))`で指定されたセッターの形式。
+    setter: SetterAttrs,
+}
+
+/// `builder(each(name = "...", item = "..."))`のように指定された、要素を追加するメソッド。
+struct EachAttrs {
+    /// メソッドの名前。
+    name: Ident,
+    /// コレクションの要素の型。
+    /// 標準ライブラリ以外のコレクション型の場合に指定する。
+    item: Option<Type>,
+}
+
+/// `builder(setter(into, strip_option = false))`のように指定されたセッターの形式。
+#[derive(Default)]
+struct SetterAttrs {
+    /// `into`が指定された場合は`Some(true)`。
+    into: Option<bool>,
+    /// `strip_option`が指定された場合は、その値。
+    strip_option: Option<bool>,
+    /// `prefix = "..."`で指定されたセッターの名前の接頭辞。
+    prefix: Option<LitStr>,
+}
+
+/// 構造体に付与された`builder`属性の内容。
+#[derive(Default)]
+struct StructAttrs {
+    /// `builder(name = "...")`で指定されたビルダーの名前。
+    name: Option<Ident>,
+    /// `builder(constructor = "...")`で指定された、ビルダーを作成する関数の名前。
+    constructor: Option<Ident>,
+    /// `builder(build_fn(name = "..."))`で指定されたbuildメソッドの名前。
+    build_fn: Option<Ident>,
+    /// `builder(vis = "...")`で指定されたビルダーの可視性。
+    vis: Option<Visibility>,
+    /// `builder(serde)`が指定されている場合は`true`。
+    serde: bool,
+    /// `builder(no_std)`が指定された場合は`Some(false)`、`builder(no_std(alloc))`が
+    /// 指定された場合は`Some(true)`。
+    no_std: Option<bool>,
+    /// `builder(derive(...))`で指定された、ビルダーにderiveするトレイト。
+    derives: Vec<Path>,
+    /// `builder(struct_attrs(...))`で指定された、ビルダーに付与する属性。
+    attrs: Vec<Meta>,
+    /// `builder(typestate)`が指定されている場合は`true`。
+    typestate: bool,
+    /// `builder(const)`が指定されている場合は`true`。
+    constant: bool,
+    /// `builder(default)`が指定されている場合は、その`default`のパス。
+    default: Option<Path>,
+    /// `builder(validate = "...")`で指定された、構築した値を検証する関数。
+    validate: Option<Expr>,
+    /// `builder(error = "...")`で指定された、buildメソッドが返すエラーの型。
+    error: Option<Type>,
+    /// `builder(setter(...))`で指定された、すべてのセッターの形式。
+    setter: SetterAttrs,
+    /// `builder(pattern = "...")`で指定されたビルダーのパターン。
+    pattern: BuilderPattern,
+}
+
+impl StructAttrs {
+    /// buildメソッドの名前を返す。
+    fn build_ident(&self) -> Ident {
+        self.build_fn
+            .clone()
+            .unwrap_or_else(|| format_ident!("build"))
+    }
+
+    /// 生成したコードが使用する標準ライブラリのクレートを返す。
+    ///
+    /// `builder(no_std)`が指定された場合は、`alloc`クレートを使用するコレクション型の
+    /// フィールド、または内側のビルダーを格納するフィールドがある場合のみ`alloc`クレートを
+    /// 使用する。`builder(no_std(alloc))`が指定された場合は、常に`alloc`クレートを使用する。
+    fn std_lib(&self, fields: &[BuilderField]) -> StdLib {
+        let needs_alloc = |f: &BuilderField| {
+            f.sub_builder.is_some()
+                || matches!(determine_field_type(&f.ty), FieldType::Collection(_))
+        };
+        match self.no_std {
+            None => StdLib::Std,
+            Some(true) => StdLib::Alloc,
+            Some(false) if fields.iter().any(needs_alloc) => StdLib::Alloc,
+            Some(false) => StdLib::Core,
+        }
+    }
+
+    /// buildメソッドが返すエラーの型を返す。
+    ///
+    /// `builder(error = "...")`で型が指定されていない場合は、ビルダーごとに生成する
+    /// エラー型を返す。指定された型は、生成したエラー型及び検証関数が返すエラー型からの
+    /// `From`を実装している必要がある。
+    fn error_ty(&self, error_ident: &Ident) -> TokenStream2 {
+        match &self.error {
+            Some(error) => error.to_token_stream(),
+            None => error_ident.to_token_stream(),
+        }
+    }
+}
+
+/// 必須フィールドの値が設定されているかを実行時に確認するビルダーを実装する。
+///
+/// セッター及びbuildメソッドのレシーバーは、`builder(pattern = "...")`で指定された
+/// パターンに従う。`immutable`パターンの場合、ビルダーはフィールドを複製して新しい
+/// ビルダーを返すため、ビルダーに`Clone`を実装する。
+fn impl_runtime_builder(
+    ident: &Ident,
+    generics: &Generics,
+    struct_attrs: &StructAttrs,
+    target: &BuilderTarget,
+) -> TokenStream2 {
+    let BuilderTarget {
+        builder_ident,
+        vis,
+        constructor_ident,
+        fields,
+        ..
+    } = target;
+    let pattern = struct_attrs.pattern;
+    let build_ident = struct_attrs.build_ident();
+    let error_ident = format_ident!("{}Error", builder_ident);
+    let error_ty = struct_attrs.error_ty(&error_ident);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // `immutable`パターンの場合は、フィールドの型が`Clone`を実装していることを要求
+    let mut builder_generics = generics.clone();
+    if pattern == BuilderPattern::Immutable {
+        let where_clause = builder_generics.make_where_clause();
+        for f in fields {
+            let slot_ty = f.slot_ty();
+            where_clause
+                .predicates
+                .push(parse_quote! { #slot_ty: ::core::clone::Clone });
+        }
+    }
+    let builder_where_clause = &builder_generics.where_clause;
+
+    let field_idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    // ビルダーのフィールドを作成
+    // `builder(serde)`が指定された場合は、設定ファイルなどから一部のフィールドだけを
+    // 読み込めるように、値が設定されていないフィールドは読み書きしない
+    let serde = struct_attrs.serde;
+    let builder_fields = fields.iter().map(|f| {
+        let (ident, slot_ty) = (&f.ident, f.slot_ty());
+        let serde_attrs = if serde {
+            let SerdeNames {
+                serialize,
+                deserialize,
+            } = &f.serde_names;
+            let forwarded = &f.serde_attrs;
+            quote! {
+                #[serde(
+                    rename(serialize = #serialize, deserialize = #deserialize),
+                    #(#forwarded,)*
+                    default,
+                    skip_serializing_if = "::core::option::Option::is_none"
+                )]
+            }
+        } else {
+            quote! {}
+        };
+        quote! {
+            #serde_attrs
+            #ident: ::core::option::Option<#slot_ty>
+        }
+    });
+    let (serde_derive, serde_skip) = if serde {
+        (
+            quote! { #[derive(::serde::Serialize, ::serde::Deserialize)] },
+            quote! { #[serde(skip)] },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+    let builder_methods = fields
+        .iter()
+        .map(|f| impl_builder_method(f, &f.setter_vis(vis), pattern));
+    let builder_merge = impl_builder_merge(fields, vis, pattern);
+    let (field_info, fields_const, field_info_methods) = target.impl_field_info(serde);
+    let from_value =
+        target.impl_from_value(ident, generics, &quote! { #builder_ident #ty_generics });
+    // 値が設定されていない必須フィールドを収集するコードを作成
+    // `builder(serde)`が指定された場合は、serdeで読み書きするときの名前で報告する
+    let required_fields = target.required_fields();
+    let required_idents = required_fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let required_names = required_fields
+        .iter()
+        .map(|f| f.report_name(serde))
+        .collect::<Vec<_>>();
+    // アロケーターを使用しない場合は、必須フィールドの数の配列に名前を格納する
+    let report_missing = target.report_missing(serde);
+    // 内側のビルダーに値が設定されていないフィールドがある場合は、外側のビルダーの
+    // フィールドとあわせてパスで報告する
+    let has_nested = fields.iter().any(|f| f.sub_builder.is_some());
+    let check_nested_fields = has_nested.then(|| {
+        let alloc = target.std_lib.alloc_crate();
+        quote! {
+            let mut paths: #alloc::vec::Vec<#alloc::string::String> = #alloc::vec::Vec::new();
+            self.__missing_paths(&mut |path: &str| {
+                paths.push(#alloc::borrow::ToOwned::to_owned(path));
+            });
+            if paths.len() != missing.len() {
+                return ::core::result::Result::Err(::core::convert::From::from(
+                    #error_ident::MissingNestedFields(paths),
+                ));
+            }
+        }
+    });
+    let check_missing_fields = match (required_idents.len(), target.std_lib) {
+        (0, _) if !has_nested => quote! {},
+        (len, StdLib::Core) => quote! {
+            let mut missing: [&'static str; #len] = [""; #len];
+            let mut len = 0;
+            #(
+                if self.#required_idents.is_none() {
+                    missing[len] = #required_names;
+                    len += 1;
+                }
+            )*
+            let missing = match len {
+                0 => ::core::option::Option::None,
+                1 => ::core::option::Option::Some(#error_ident::MissingField(missing[0])),
+                _ => ::core::option::Option::Some(#error_ident::MissingFields(missing, len)),
+            };
+            if let ::core::option::Option::Some(err) = missing {
+                return ::core::result::Result::Err(::core::convert::From::from(err));
+            }
+        },
+        (_, std_lib) => {
+            let alloc = std_lib.alloc_crate();
+            quote! {
+            #[allow(unused_mut)]
+            let mut missing: #alloc::vec::Vec<&'static str> = #alloc::vec::Vec::new();
+            #(
+                if self.#required_idents.is_none() {
+                    missing.push(#required_names);
+                }
+            )*
+            #check_nested_fields
+            let missing = match missing.len() {
+                0 => ::core::option::Option::None,
+                1 => ::core::option::Option::Some(#error_ident::MissingField(missing[0])),
+                _ => ::core::option::Option::Some(#error_ident::MissingFields(missing)),
+            };
+            if let ::core::option::Option::Some(err) = missing {
+                return ::core::result::Result::Err(::core::convert::From::from(err));
+            }
+            }
+        }
+    };
+    let (build_receiver, stored): (_, fn(&Ident) -> TokenStream2) = match pattern {
+        BuilderPattern::Mutable => (quote! { &mut self }, |ident| quote! { self.#ident.take() }),
+        BuilderPattern::Owned => (quote! { self }, |ident| quote! { self.#ident }),
+        BuilderPattern::Immutable => (quote! { &self }, |ident| {
+            quote! { ::core::clone::Clone::clone(&self.#ident) }
+        }),
+    };
+    let build_value = target.build_value(
+        ident,
+        &ty_generics,
+        struct_attrs.validate.as_ref(),
+        &error_ident,
+        serde,
+        stored,
+    );
+    let builder_error =
+        impl_builder_error(vis, &error_ident, target.std_lib, required_idents.len());
+    let (builder_attrs, builder_debug) = impl_builder_attrs(
+        struct_attrs,
+        builder_ident,
+        &quote! { #builder_ident #ty_generics },
+        generics,
+        fields,
+    );
+    let builder_clone = if pattern == BuilderPattern::Immutable {
+        quote! {
+            impl #impl_generics ::core::clone::Clone for #builder_ident #ty_generics
+                #builder_where_clause
+            {
+                fn clone(&self) -> Self {
+                    #builder_ident {
+                        #(#field_idents: ::core::clone::Clone::clone(&self.#field_idents),)*
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #builder_attrs
+        #serde_derive
+        #vis struct #builder_ident #generics #where_clause {
+            #(#builder_fields,)*
+            #serde_skip
+            __marker: ::core::marker::PhantomData<fn() -> #ident #ty_generics>,
+        }
+
+        #builder_error
+
+        #field_info
+
+        #builder_clone
+
+        #builder_debug
+
+        // 内側のビルダーとして使用できるように、値が設定されていないビルダーを作成する
+        impl #impl_generics ::core::default::Default for #builder_ident #ty_generics #where_clause {
+            fn default() -> Self {
+                #builder_ident {
+                    #(#field_idents: ::core::option::Option::None,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #impl_generics #builder_ident #ty_generics #builder_where_clause {
+            #(#builder_methods)*
+
+            #builder_merge
+
+            #fields_const
+
+            #field_info_methods
+
+            #vis fn #build_ident(#build_receiver) -> ::core::result::Result<#ident #ty_generics, #error_ty> {
+                #check_missing_fields
+                ::core::result::Result::Ok(#build_value)
+            }
+
+            /// 外側のビルダーが値を構築するときに呼び出す。
+            #[doc(hidden)]
+            #vis fn __sub_build(#build_receiver) -> ::core::result::Result<#ident #ty_generics, #error_ty> {
+                self.#build_ident()
+            }
+
+            /// 外側のビルダーが値を構築するときに、値が設定されていない必須フィールドの
+            /// パスを`report`に渡す。
+            #[doc(hidden)]
+            #vis fn __missing_paths(&self, report: &mut dyn ::core::ops::FnMut(&str)) {
+                #report_missing
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #vis fn #constructor_ident() -> #builder_ident #ty_generics {
+                ::core::default::Default::default()
+            }
+        }
+
+        #from_value
+    }
+}
+
+/// 定数式で値を構築できるビルダーを実装する。
+///
+/// ビルダーは`owned`パターンで、セッター、buildメソッド及びビルダーを作成する関数は
+/// `const fn`である。必須フィールドに値が設定されていない場合、buildメソッドは
+/// パニックするため、定数の初期化式では最初に見つかった値が設定されていないフィールドが
+/// コンパイルエラーとして報告される。`const fn`では値を破棄できないため、フィールドの
+/// 型はデストラクタを持たない型である必要がある。
+fn impl_const_builder(
+    ident: &Ident,
+    generics: &Generics,
+    struct_attrs: &StructAttrs,
+    target: &BuilderTarget,
+) -> TokenStream2 {
+    let BuilderTarget {
+        builder_ident,
+        vis,
+        constructor_ident,
+        path,
+        fields,
+        skipped_fields,
+        ..
+    } = target;
+    let build_ident = struct_attrs.build_ident();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let field_idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let builder_fields = fields.iter().map(|f| {
+        let (ident, field_ty) = (&f.ident, &f.ty);
+        quote! { #ident: ::core::option::Option<#field_ty> }
+    });
+    let setters = fields.iter().map(|f| {
+        let (ident, setter_ident) = (&f.ident, &f.setter_ident);
+        let setter_vis = f.setter_vis(vis);
+        let (arg_ty, value) = f.setter_arg();
+        quote! {
+            #setter_vis const fn #setter_ident(mut self, #ident: #arg_ty) -> Self {
+                self.#ident = ::core::option::Option::Some(#value);
+                self
+            }
+        }
+    });
+    // `Option`のメソッドはクロージャを受け取るため、`match`でフィールドの値を取り出す
+    let values = fields.iter().map(|f| {
+        let ident = &f.ident;
+        let fallback = f.fallback().unwrap_or_else(|| {
+            let message = format!("{} is not provided", f.report_name(false));
+            quote! { ::core::panic!(#message) }
+        });
+        quote! {
+            let #ident = match self.#ident {
+                ::core::option::Option::Some(value) => value,
+                ::core::option::Option::None => #fallback,
+            };
+        }
+    });
+    let members = fields.iter().map(|f| &f.member);
+    let skipped_members = skipped_fields.iter().map(|f| &f.member);
+    let skipped_values = skipped_fields.iter().map(|f| &f.value);
+    let builder_merge = impl_builder_merge(fields, vis, BuilderPattern::Owned);
+    let (field_info, fields_const, field_info_methods) = target.impl_field_info(false);
+    let from_value =
+        target.impl_from_value(ident, generics, &quote! { #builder_ident #ty_generics });
+    let (builder_attrs, builder_debug) = impl_builder_attrs(
+        struct_attrs,
+        builder_ident,
+        &quote! { #builder_ident #ty_generics },
+        generics,
+        fields,
+    );
+    let new_builder = quote! {
+        #builder_ident {
+            #(#field_idents: ::core::option::Option::None,)*
+            __marker: ::core::marker::PhantomData,
+        }
+    };
+
+    quote! {
+        #builder_attrs
+        #vis struct #builder_ident #generics #where_clause {
+            #(#builder_fields,)*
+            __marker: ::core::marker::PhantomData<fn() -> #ident #ty_generics>,
+        }
+
+        #builder_debug
+
+        #field_info
+
+        impl #impl_generics ::core::default::Default for #builder_ident #ty_generics #where_clause {
+            fn default() -> Self {
+                #new_builder
+            }
+        }
+
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            #(#setters)*
+
+            #builder_merge
+
+            #fields_const
+
+            #field_info_methods
+
+            #vis const fn #build_ident(self) -> #ident #ty_generics {
+                #(#values)*
+                #path {
+                    #(#members: #field_idents,)*
+                    #(#skipped_members: #skipped_values,)*
+                }
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #vis const fn #constructor_ident() -> #builder_ident #ty_generics {
+                #new_builder
+            }
+        }
+
+        #from_value
+    }
+}
+
+/// 必須フィールドの値が設定されているかをコンパイル時に確認するビルダーを実装する。
+///
+/// ビルダーは必須フィールドごとに型パラメーターを持ち、その型パラメーターは値が設定
+/// されていない場合は`Unset`、設定されている場合は`Set`になる。必須フィールドの
+/// セッターは、そのフィールドの型パラメーターを`Set`に変更したビルダーを返す。
+/// buildメソッドは、すべての型パラメーターが`Set`の場合のみ実装されるため、必須
+/// フィールドの値を設定し忘れた場合はコンパイルエラーになる。
+fn impl_typestate_builder(
+    ident: &Ident,
+    generics: &Generics,
+    struct_attrs: &StructAttrs,
+    target: &BuilderTarget,
+) -> TokenStream2 {
+    let BuilderTarget {
+        builder_ident,
+        vis,
+        constructor_ident,
+        fields,
+        ..
+    } = target;
+    let state_mod = format_ident!("{}", to_snake_case(&builder_ident.to_string()));
+    let build_ident = struct_attrs.build_ident();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let args = generic_args(generics);
+
+    let field_idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let builder_fields = fields.iter().map(|f| {
+        let (ident, field_ty) = (&f.ident, &f.ty);
+        quote! { #ident: ::core::option::Option<#field_ty>}
+    });
+    // 必須フィールドごとに、値が設定されているかを表現する型パラメーターを作成
+    // フィールドの名前から作成すると、`x_y`と`x__y`のように名前が重複する場合があるため、
+    // 必須フィールドの位置から名前を付ける
+    let required_fields = target.required_fields();
+    let state_params = (0..required_fields.len())
+        .map(|i| format_ident!("__State{}", i))
+        .collect::<Vec<_>>();
+    // 構造体のジェネリックパラメーターの後ろに、型状態を表現する型パラメーターを追加
+    let mut state_generics = generics.clone();
+    for param in &state_params {
+        state_generics
+            .params
+            .push(parse_quote! { #param = #state_mod::Unset });
+    }
+    let (state_impl_generics, _, _) = state_generics.split_for_impl();
+    let (builder_attrs, builder_debug) = impl_builder_attrs(
+        struct_attrs,
+        builder_ident,
+        &quote! { #builder_ident<#(#args,)* #(#state_params),*> },
+        &state_generics,
+        fields,
+    );
+    let marker = target.marker_ty(ident, generics);
+    let output_ty = target.output_ty(ident, &ty_generics);
+    let unset_states = state_params.iter().map(|_| quote! { #state_mod::Unset });
+    let unset_builder = quote! { #builder_ident<#(#args,)* #(#unset_states),*> };
+    // 型パラメーターを指定せずに`FIELDS`を参照できるように、値が設定されていない状態の
+    // ビルダーにのみ実装する
+    let (field_info, fields_const, field_info_methods) = target.impl_field_info(false);
+    let set_states = state_params
+        .iter()
+        .map(|_| quote! { #state_mod::Set })
+        .collect::<Vec<_>>();
+    // 構造体の値から作成したビルダーは、すべての必須フィールドに値が設定されている
+    let from_value = target.impl_from_value(
+        ident,
+        generics,
+        &quote! { #builder_ident<#(#args,)* #(#set_states),*> },
+    );
+    // 必須フィールドのセッターは、そのフィールドの型パラメーターを`Set`に変更する
+    let required_setters = required_fields.iter().enumerate().map(|(i, f)| {
+        let (ident, setter_ident) = (&f.ident, &f.setter_ident);
+        let setter_vis = f.setter_vis(vis);
+        let (arg_ty, value) = f.setter_arg();
+        let next_states = state_params.iter().enumerate().map(|(j, param)| {
+            if i == j {
+                quote! { #state_mod::Set }
+            } else {
+                quote! { #param }
+            }
+        });
+        let other_idents = field_idents.iter().filter(|other| *other != &ident);
+        let next_builder = quote! { #builder_ident<#(#args,)* #(#next_states),*> };
+        let try_setter = impl_try_setter(f, &setter_vis, &quote! { self }, &next_builder);
+        quote! {
+            #setter_vis fn #setter_ident(self, #ident: #arg_ty) -> #next_builder {
+                #builder_ident {
+                    #ident: ::core::option::Option::Some(#value),
+                    #(#other_idents: self.#other_idents,)*
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+
+            #try_setter
         }
-        let field_idents = fields.iter().map(|(ident, _)| ident);
-        // ビルダーのフィールドを作成
-        let builder_fields = fields
-            .iter()
-            .map(|(ident, field_ty)| quote! { #ident: ::core::option::Option<#field_ty>});
-        let builder_init_fields = fields.iter().map(builder_init_field);
-        let each_attributes = named_fields
-            .iter()
-            .map(|f| match f.attrs.first() {
-                Some(attr) => inspect_each(attr),
-                None => Ok(None),
-            })
-            .collect::<Result<Vec<_>>>()?;
-        let builder_methods =
-            fields
+    });
+    let optional_setters = fields
+        .iter()
+        .filter(|f| {
+            !required_fields
                 .iter()
-                .zip(each_attributes)
-                .map(|((identifier, field_type), maybe_each)| {
-                    impl_builder_method(identifier, field_type, maybe_each)
-                });
+                .any(|required| required.ident == f.ident)
+        })
+        .map(|f| impl_builder_method(f, &f.setter_vis(vis), BuilderPattern::Owned));
+    let error_ident = format_ident!("{}Error", builder_ident);
+    let build_value = target.build_value(
+        ident,
+        &ty_generics,
+        struct_attrs.validate.as_ref(),
+        &error_ident,
+        false,
+        |ident| quote! { self.#ident },
+    );
+    // 検証関数が指定されている場合のみ、buildメソッドはエラーを返す
+    let (build_ret, build_value, builder_error) =
+        if target.has_validation(struct_attrs.validate.as_ref()) {
+            let error_ty = struct_attrs.error_ty(&error_ident);
+            (
+                quote! { ::core::result::Result<#output_ty, #error_ty> },
+                quote! { ::core::result::Result::Ok(#build_value) },
+                impl_builder_error(vis, &error_ident, target.std_lib, 0),
+            )
+        } else {
+            (output_ty, build_value, quote! {})
+        };
+    // 関数の引数のビルダーの場合、buildメソッドは関数と同じ修飾子を持ち、ビルダーを作成する
+    // 関数は関数と同じモジュールに作成する
+    let (asyncness, unsafety) = match &target.kind {
+        TargetKind::Function(function) => (function.asyncness, function.unsafety),
+        TargetKind::Struct | TargetKind::Variant => (None, None),
+    };
+    let new_builder = quote! {
+        #builder_ident {
+            #(#field_idents: ::core::option::Option::None,)*
+            __marker: ::core::marker::PhantomData,
+        }
+    };
+    let constructor = match &target.kind {
+        TargetKind::Function(_) => quote! {
+            #vis fn #constructor_ident #impl_generics() -> #unset_builder #where_clause {
+                #new_builder
+            }
+        },
+        TargetKind::Struct | TargetKind::Variant => quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #vis fn #constructor_ident() -> #unset_builder {
+                    #new_builder
+                }
+            }
+        },
+    };
+
+    quote! {
+        #vis mod #state_mod {
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+            pub struct Unset;
+
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            pub struct Set;
+        }
+
+        #builder_error
 
-        Ok(quote! {
-            struct #builder_ident {
-                #(#builder_fields),*
+        #builder_attrs
+        #vis struct #builder_ident #state_generics #where_clause {
+            #(#builder_fields,)*
+            __marker: ::core::marker::PhantomData<(#marker, #(#state_params,)*)>,
+        }
+
+        #builder_debug
+
+        #field_info
+
+        impl #state_impl_generics #builder_ident<#(#args,)* #(#state_params),*> #where_clause {
+            #(#required_setters)*
+            #(#optional_setters)*
+
+            #field_info_methods
+        }
+
+        impl #impl_generics #unset_builder #where_clause {
+            #fields_const
+        }
+
+        impl #impl_generics #builder_ident<#(#args,)* #(#set_states),*> #where_clause {
+            #vis #asyncness #unsafety fn #build_ident(self) -> #build_ret {
+                #build_value
             }
+        }
 
-            impl #builder_ident {
-                #(#builder_methods)*
+        #constructor
 
-                fn build(&mut self) -> ::core::result::Result<
-                    #ident,
-                    ::std::boxed::Box<dyn ::std::error::Error>>
-                {
-                    Ok(#ident {
-                        #(
-                            #field_idents:
-                                self.#field_idents.take().ok_or_else(||
-                                    format!("{} is not provided", stringify!(#field_idents))
-                            )?,
-                        )*
-                    })
+        #from_value
+    }
+}
+
+/// ビルダーに付与する属性と、`builder(derive(Debug))`が指定された場合のビルダーの
+/// `Debug`実装を作成する。
+///
+/// `Debug`はderiveせずに、値が設定されたフィールドはその値を、値が設定されていない
+/// フィールドは`<unset>`を表示するように実装する。`immutable`パターンのビルダーには
+/// `Clone`を、型状態を持たないビルダーには`Default`を実装するため、それらのderiveは
+/// 無視する。型状態を持つビルダーには`Default`を指定できない。
+fn impl_builder_attrs(
+    struct_attrs: &StructAttrs,
+    builder_ident: &Ident,
+    builder_ty: &TokenStream2,
+    generics: &Generics,
+    fields: &[BuilderField],
+) -> (TokenStream2, TokenStream2) {
+    let derives = struct_attrs
+        .derives
+        .iter()
+        .filter(|path| !path.is_ident("Debug"))
+        .filter(|path| {
+            !(path.is_ident("Clone") && struct_attrs.pattern == BuilderPattern::Immutable)
+        })
+        .filter(|path| !path.is_ident("Default"))
+        .collect::<Vec<_>>();
+    let derive = if derives.is_empty() {
+        quote! {}
+    } else {
+        quote! { #[derive(#(#derives),*)] }
+    };
+    let attrs = &struct_attrs.attrs;
+    let builder_attrs = quote! {
+        #derive
+        #(#[#attrs])*
+    };
+    if !struct_attrs
+        .derives
+        .iter()
+        .any(|path| path.is_ident("Debug"))
+    {
+        return (builder_attrs, quote! {});
+    }
+
+    // フィールドの型が`Debug`を実装していることを要求
+    let mut debug_generics = generics.clone();
+    let where_clause = debug_generics.make_where_clause();
+    for f in fields {
+        let slot_ty = f.slot_ty();
+        where_clause
+            .predicates
+            .push(parse_quote! { #slot_ty: ::core::fmt::Debug });
+    }
+    let (impl_generics, _, where_clause) = debug_generics.split_for_impl();
+    let builder_name = builder_ident.to_string();
+    let debug_fields = fields.iter().map(|f| {
+        let ident = &f.ident;
+        let name = ident.unraw().to_string();
+        quote! {
+            match &self.#ident {
+                ::core::option::Option::Some(value) => debug.field(#name, value),
+                ::core::option::Option::None => {
+                    debug.field(#name, &::core::format_args!("<unset>"))
                 }
+            };
+        }
+    });
+    let builder_debug = quote! {
+        impl #impl_generics ::core::fmt::Debug for #builder_ty #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let mut debug = f.debug_struct(#builder_name);
+                #(#debug_fields)*
+                debug.finish()
             }
+        }
+    };
 
-            impl #ident {
-                fn builder() -> #builder_ident {
-                    #builder_ident {
-                        #(#builder_init_fields),*
+    (builder_attrs, builder_debug)
+}
+
+/// ビルダーのbuildメソッドが返すエラー型を実装する。
+///
+/// 値が設定されていない必須フィールドが1つの場合は`MissingField`、複数の場合は
+/// `MissingFields`で、値が設定されていないすべてのフィールドの名前を返す。
+/// 内側のビルダーに値が設定されていないフィールドがある場合は`MissingNestedFields`で、
+/// `tls.cert_path`のようなフィールドのパスを返す。
+/// 検証関数が`String`のエラーを返した場合は`ValidationFailed`を返す。
+///
+/// アロケーターを使用しない場合、`MissingFields`は必須フィールドの数`required_len`の
+/// 配列と、その配列に格納した名前の数を持つ。`ValidationFailed`は検証関数が返した
+/// `&'static str`のエラーを持つ。
+fn impl_builder_error(
+    vis: &Visibility,
+    error_ident: &Ident,
+    std_lib: StdLib,
+    required_len: usize,
+) -> TokenStream2 {
+    let error_trait = std_lib.error_trait();
+    if std_lib == StdLib::Core {
+        return quote! {
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis enum #error_ident {
+                MissingField(&'static str),
+                MissingFields([&'static str; #required_len], usize),
+                ValidationFailed(&'static str),
+            }
+
+            impl ::core::fmt::Display for #error_ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        Self::MissingField(field) => ::core::write!(f, "{} is not provided", field),
+                        Self::MissingFields(fields, len) => {
+                            for (i, field) in fields[..*len].iter().enumerate() {
+                                if i != 0 {
+                                    f.write_str(", ")?;
+                                }
+                                f.write_str(field)?;
+                            }
+                            f.write_str(" are not provided")
+                        }
+                        Self::ValidationFailed(message) => f.write_str(message),
                     }
                 }
             }
-        })
-    } else {
-        Err(Error::new(input.span(), "Only struct supported"))
-    }
-}
 
-/// 構造体のフィールドの型がOptionの場合、そのフィールドに対応するビルダーのフィールドは、
-/// 値が設定されていないことを示すために、二重のSomeでラップする必要がある。
-/// builder.option_field = Some(Some(...))
-/// builder.option_field = Some(None)
-/// 二重のSomeでラップしない場合、ビルダーのbuildメソッド内で実行するOption::take()メソッドが
-/// 失敗する。
-fn impl_builder_method(identifier: &Ident, field_type: &Type, each: Option<Ident>) -> TokenStream2 {
-    let has_each = each.is_some();
-    match determine_field_type(field_type) {
-        FieldType::Option(inner_type) => {
-            quote! {
-                fn #identifier(&mut self, #identifier: #inner_type) -> &mut Self {
-                    self.#identifier = ::core::option::Option::Some(
-                        ::core::option::Option::Some(#identifier)
-                    );
-                    self
+            impl #error_trait for #error_ident {}
+
+            impl ::core::convert::From<&'static str> for #error_ident {
+                fn from(message: &'static str) -> Self {
+                    Self::ValidationFailed(message)
                 }
             }
+        };
+    }
+    let alloc = std_lib.alloc_crate();
+
+    quote! {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis enum #error_ident {
+            MissingField(&'static str),
+            MissingFields(#alloc::vec::Vec<&'static str>),
+            MissingNestedFields(#alloc::vec::Vec<#alloc::string::String>),
+            ValidationFailed(#alloc::string::String),
         }
-        FieldType::Vec(inner_type) if has_each => {
-            let each = each.unwrap();
-            quote! {
-                fn #each(&mut self, #each: #inner_type) -> &mut Self {
-                    self.#identifier.as_mut().map(|v| v.push(#each));
-                    self
+
+        impl #error_ident {
+            /// 値が設定されていないフィールドのパスを返す。
+            #vis fn missing_paths(&self) -> #alloc::vec::Vec<#alloc::string::String> {
+                match self {
+                    Self::MissingField(field) => #alloc::vec![#alloc::string::ToString::to_string(field)],
+                    Self::MissingFields(fields) => fields
+                        .iter()
+                        .map(#alloc::string::ToString::to_string)
+                        .collect(),
+                    Self::MissingNestedFields(paths) => ::core::clone::Clone::clone(paths),
+                    Self::ValidationFailed(_) => #alloc::vec::Vec::new(),
                 }
             }
         }
-        _ => {
-            quote! {
-                fn #identifier(&mut self, #identifier: #field_type) -> &mut Self {
-                    self.#identifier = ::core::option::Option::Some(#identifier);
-                    self
+
+        impl ::core::fmt::Display for #error_ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    Self::MissingField(field) => ::core::write!(f, "{} is not provided", field),
+                    Self::MissingFields(fields) => {
+                        ::core::write!(f, "{} are not provided", fields.join(", "))
+                    }
+                    Self::MissingNestedFields(paths) => match paths.as_slice() {
+                        [path] => ::core::write!(f, "{} is not provided", path),
+                        _ => ::core::write!(f, "{} are not provided", paths.join(", ")),
+                    },
+                    Self::ValidationFailed(message) => f.write_str(message),
                 }
             }
         }
+
+        impl #error_trait for #error_ident {}
+
+        impl ::core::convert::From<#alloc::string::String> for #error_ident {
+            fn from(message: #alloc::string::String) -> Self {
+                Self::ValidationFailed(message)
+            }
+        }
+    }
+}
+
+/// 生成したコードが使用する標準ライブラリのクレート。
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum StdLib {
+    /// `std`クレートを使用する。
+    #[default]
+    Std,
+    /// `builder(no_std)`が指定された場合に、`core`クレート及び`alloc`クレートを使用する。
+    Alloc,
+    /// `builder(no_std)`が指定された場合に、`core`クレートのみを使用する。
+    Core,
+}
+
+impl StdLib {
+    /// `Vec`や`String`などを定義するクレートのパスを返す。
+    fn alloc_crate(&self) -> TokenStream2 {
+        match self {
+            StdLib::Std => quote! { ::std },
+            StdLib::Alloc | StdLib::Core => quote! { ::alloc },
+        }
+    }
+
+    /// `Error`トレイトのパスを返す。
+    fn error_trait(&self) -> TokenStream2 {
+        match self {
+            StdLib::Std => quote! { ::std::error::Error },
+            StdLib::Alloc | StdLib::Core => quote! { ::core::error::Error },
+        }
+    }
+}
+
+/// ビルダーのパターン。
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum BuilderPattern {
+    /// セッターは`&mut self`を受け取り、`&mut Self`を返す。
+    /// buildメソッドは`&mut self`を受け取り、ビルダーから値を取り出す。
+    #[default]
+    Mutable,
+    /// セッターは`self`を受け取り、`Self`を返す。
+    /// buildメソッドは`self`を受け取り、ビルダーを消費する。
+    Owned,
+    /// セッターは`&self`を受け取り、値を設定した新しいビルダーを返す。
+    /// buildメソッドは`&self`を受け取り、ビルダーの値を複製する。
+    Immutable,
+}
+
+impl BuilderPattern {
+    /// セッターのレシーバーと戻り値の型、及び値を設定するビルダーを返す。
+    ///
+    /// `immutable`パターンの場合は、ビルダーを複製した`builder`に値を設定する。
+    fn setter_signature(&self) -> (TokenStream2, TokenStream2, TokenStream2) {
+        match self {
+            BuilderPattern::Mutable => (quote! { &mut self }, quote! { &mut Self }, quote! {}),
+            BuilderPattern::Owned => (quote! { mut self }, quote! { Self }, quote! {}),
+            BuilderPattern::Immutable => (
+                quote! { &self },
+                quote! { Self },
+                quote! { let mut builder = ::core::clone::Clone::clone(self); },
+            ),
+        }
+    }
+
+    /// セッターが値を設定して返すビルダーを返す。
+    fn setter_target(&self) -> TokenStream2 {
+        match self {
+            BuilderPattern::Mutable | BuilderPattern::Owned => quote! { self },
+            BuilderPattern::Immutable => quote! { builder },
+        }
+    }
+}
+
+/// ビルダーのフィールドは、値が設定されていないことを`None`で表現する。
+/// 構造体のフィールドの型がOptionの場合、そのフィールドに対応するビルダーのフィールドは、
+/// 値が設定されていることを示すために、二重のSomeでラップする。
+/// builder.option_field = Some(Some(...))
+/// `builder(each = "...")`属性が付与されたフィールドは、最初に要素を追加するときに
+/// 空のコレクションを設定し、`Extend`で要素を追加する。マップ型のフィールドの場合、
+/// メソッドはキーと値を受け取る。
+///
+/// コレクション全体を設定するセッターに加えて、要素を1つ追加するメソッドと、
+/// 複数の要素を追加する`extend_<field>`メソッドを作成する。要素を追加するメソッドの
+/// 名前がフィールドと同じ場合は、コレクション全体を設定するセッターは作成しない。
+fn impl_builder_method(
+    field: &BuilderField,
+    vis: &Visibility,
+    pattern: BuilderPattern,
+) -> TokenStream2 {
+    let (receiver, ret, prologue) = pattern.setter_signature();
+    let builder = pattern.setter_target();
+    let identifier = &field.ident;
+    let setter_ident = &field.setter_ident;
+    // 内側のビルダーを格納するフィールドのセッターは、内側のビルダーを変更する関数を受け取る
+    if let Some(sub_builder) = &field.sub_builder {
+        return quote! {
+            #vis fn #setter_ident(
+                #receiver,
+                f: impl ::core::ops::FnOnce(&mut #sub_builder) -> &mut #sub_builder,
+            ) -> #ret {
+                #prologue
+                f(#builder.#identifier.get_or_insert_with(::core::default::Default::default));
+                #builder
+            }
+        };
+    }
+    let setter = if field.each.as_ref() == Some(setter_ident) {
+        quote! {}
+    } else {
+        let (arg_ty, value) = field.setter_arg();
+        let try_receiver = match pattern {
+            BuilderPattern::Mutable => quote! { &mut self },
+            BuilderPattern::Owned => quote! { self },
+            BuilderPattern::Immutable => quote! { &self },
+        };
+        let try_setter = impl_try_setter(field, vis, &try_receiver, &ret);
+        quote! {
+            #vis fn #setter_ident(#receiver, #identifier: #arg_ty) -> #ret {
+                #prologue
+                #builder.#identifier = ::core::option::Option::Some(#value);
+                #builder
+            }
+
+            #try_setter
+        }
+    };
+    let (item, each) = match (field.field_type(), &field.each) {
+        (FieldType::Collection(item), Some(each)) => (item, each),
+        _ => return setter,
+    };
+    let convert = |arg: &Ident, ty: &Type| {
+        if field.into {
+            (
+                quote! { #arg: impl ::core::convert::Into<#ty> },
+                quote! { ::core::convert::Into::into(#arg) },
+            )
+        } else {
+            (quote! { #arg: #ty }, quote! { #arg })
+        }
+    };
+    let (args, value, item_ty) = match &item {
+        CollectionItem::Value(item_ty) => {
+            let (arg, value) = convert(each, item_ty);
+            (quote! { #arg }, value, quote! { #item_ty })
+        }
+        CollectionItem::KeyValue(key_ty, value_ty) => {
+            let (key_arg, key) = convert(&format_ident!("key"), key_ty);
+            let (value_arg, value) = convert(&format_ident!("value"), value_ty);
+            (
+                quote! { #key_arg, #value_arg },
+                quote! { (#key, #value) },
+                quote! { (#key_ty, #value_ty) },
+            )
+        }
+    };
+    let extend_ident = field.extend_ident();
+
+    quote! {
+        #setter
+
+        #vis fn #each(#receiver, #args) -> #ret {
+            #prologue
+            ::core::iter::Extend::extend(
+                #builder.#identifier.get_or_insert_with(::core::default::Default::default),
+                ::core::iter::once(#value),
+            );
+            #builder
+        }
+
+        #vis fn #extend_ident(
+            #receiver,
+            #identifier: impl ::core::iter::IntoIterator<Item = #item_ty>,
+        ) -> #ret {
+            #prologue
+            ::core::iter::Extend::extend(
+                #builder.#identifier.get_or_insert_with(::core::default::Default::default),
+                #identifier,
+            );
+            #builder
+        }
     }
 }
 
-fn builder_init_field((identifier, field_type): &(Ident, Type)) -> TokenStream2 {
-    match determine_field_type(field_type) {
-        FieldType::Option(_) => {
-            quote! { #identifier: ::core::option::Option::Some(::core::option::Option::None) }
+/// 他のビルダーで値が設定されたフィールドを、ビルダーに設定するmergeメソッドを作成する。
+///
+/// 既定では他のビルダーの値で置き換える。`builder(merge = "append")`が指定された
+/// コレクション型のフィールドは、両方のビルダーに値が設定されている場合は要素を追加する。
+/// 内側のビルダーを格納するフィールドは、内側のビルダー同士をマージする。
+fn impl_builder_merge(
+    fields: &[BuilderField],
+    vis: &Visibility,
+    pattern: BuilderPattern,
+) -> TokenStream2 {
+    let (receiver, ret, prologue) = pattern.setter_signature();
+    let builder = pattern.setter_target();
+    let merge_fields = fields.iter().map(|f| {
+        let ident = &f.ident;
+        match f.merge {
+            MergeStrategy::Replace => quote! {
+                if let ::core::option::Option::Some(value) = other.#ident {
+                    #builder.#ident = ::core::option::Option::Some(value);
+                }
+            },
+            MergeStrategy::Append => quote! {
+                if let ::core::option::Option::Some(value) = other.#ident {
+                    match &mut #builder.#ident {
+                        ::core::option::Option::Some(current) => {
+                            ::core::iter::Extend::extend(current, value);
+                        }
+                        ::core::option::Option::None => {
+                            #builder.#ident = ::core::option::Option::Some(value);
+                        }
+                    }
+                }
+            },
+            MergeStrategy::Nested => quote! {
+                if let ::core::option::Option::Some(value) = other.#ident {
+                    match &mut #builder.#ident {
+                        ::core::option::Option::Some(current) => {
+                            current.merge(value);
+                        }
+                        ::core::option::Option::None => {
+                            #builder.#ident = ::core::option::Option::Some(value);
+                        }
+                    }
+                }
+            },
         }
-        FieldType::Vec(_) => {
-            quote! { #identifier: ::core::option::Option::Some(::std::vec::Vec::new()) }
+    });
+    // フィールドがない場合に、未使用の変数の警告が出ないようにする
+    let (allow_unused, unused) = if fields.is_empty() {
+        (quote! { #[allow(unused_mut)] }, quote! { let _ = other; })
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    quote! {
+        #allow_unused
+        #vis fn merge(#receiver, other: Self) -> #ret {
+            #prologue
+            #unused
+            #(#merge_fields)*
+            #builder
         }
-        FieldType::Raw => {
-            quote! { #identifier: ::core::option::Option::None }
+    }
+}
+
+/// `builder(try_setter)`が指定されたフィールドに、`TryInto`で変換した値をセッターで
+/// 設定するメソッドを作成する。
+///
+/// 変換に失敗した場合は、値を設定せずに変換のエラーを返す。
+fn impl_try_setter(
+    field: &BuilderField,
+    vis: &Visibility,
+    receiver: &TokenStream2,
+    ret: &TokenStream2,
+) -> TokenStream2 {
+    let Some(try_setter_ident) = field.try_setter_ident() else {
+        return quote! {};
+    };
+    let (setter_ident, ident) = (&field.setter_ident, &field.ident);
+    let (setter_ty, _) = field.setter_ty();
+    quote! {
+        #vis fn #try_setter_ident<__V: ::core::convert::TryInto<#setter_ty>>(
+            #receiver,
+            #ident: __V,
+        ) -> ::core::result::Result<#ret, __V::Error> {
+            let #ident: #setter_ty = ::core::convert::TryInto::try_into(#ident)?;
+            ::core::result::Result::Ok(self.#setter_ident(#ident))
         }
     }
 }
 
-enum FieldType {
+enum FieldType<'a> {
     /// 通常の型。
     Raw,
     /// オプション型。
-    Option(Type),
-    /// ベクタ型。
-    Vec(Type),
+    Option(&'a Type),
+    /// `Vec`や`HashMap`のようなコレクション型。
+    Collection(CollectionItem<'a>),
+}
+
+/// コレクション型の要素。
+enum CollectionItem<'a> {
+    /// `Vec<T>`や`HashSet<T>`のように、要素の型が`T`のコレクション。
+    Value(&'a Type),
+    /// `HashMap<K, V>`や`BTreeMap<K, V>`のように、キーと値のペアを要素とするコレクション。
+    KeyValue(&'a Type, &'a Type),
+}
+
+impl FieldType<'_> {
+    /// 型の分類から、ビルダーのフィールドに値が設定されていない場合に使用する値を返す。
+    fn fallback(&self) -> Option<TokenStream2> {
+        match self {
+            FieldType::Option(_) => Some(quote! { ::core::option::Option::None }),
+            FieldType::Collection(_) => Some(quote! { ::core::default::Default::default() }),
+            FieldType::Raw => None,
+        }
+    }
 }
 
 /// pub struct TypePath {
@@ -216,7 +2685,7 @@ enum FieldType {
 ///     pub leading_colon: Option<Colon2>,
 ///     pub segments: Punctuated<PathSegment, Colon2>,
 /// }
-fn determine_field_type(field_type: &Type) -> FieldType {
+fn determine_field_type(field_type: &Type) -> FieldType<'_> {
     if let Type::Path(TypePath {
         qself: None,
         path: Path {
@@ -225,19 +2694,45 @@ fn determine_field_type(field_type: &Type) -> FieldType {
         },
     }) = field_type
     {
-        if leading_colon.is_none() && segments.len() == 1 {
-            if let Some(PathSegment {
+        // `std::option::Option`のように、モジュールのパスを含めて指定された型も判別する
+        let modules = segments
+            .iter()
+            .take(segments.len().saturating_sub(1))
+            .map(|segment| match segment.arguments {
+                PathArguments::None => Some(segment.ident.to_string()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let last_segment = segments.last();
+        if let (
+            Some(modules),
+            Some(PathSegment {
                 ident,
                 arguments:
                     PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }),
-            }) = segments.first()
-            {
-                if let (1, Some(GenericArgument::Type(t))) = (args.len(), args.first()) {
-                    if ident == "Option" {
-                        return FieldType::Option(t.clone());
-                    } else if ident == "Vec" {
-                        return FieldType::Vec(t.clone());
+            }),
+        ) = (modules, last_segment)
+        {
+            let name = ident.to_string();
+            if (leading_colon.is_none() || !modules.is_empty()) && is_std_path(&modules, &name) {
+                let type_args = args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(t) => Some(t),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                // ハッシュ関数やアロケーターを指定する型引数は無視する
+                match (name.as_str(), type_args.as_slice()) {
+                    ("Option", [t]) => return FieldType::Option(t),
+                    (
+                        "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" | "HashSet" | "BTreeSet",
+                        [t, ..],
+                    ) => return FieldType::Collection(CollectionItem::Value(t)),
+                    ("HashMap" | "BTreeMap", [k, v, ..]) => {
+                        return FieldType::Collection(CollectionItem::KeyValue(k, v))
                     }
+                    _ => {}
                 }
             }
         }
@@ -246,46 +2741,668 @@ fn determine_field_type(field_type: &Type) -> FieldType {
     FieldType::Raw
 }
 
-fn inspect_each(attr: &Attribute) -> Result<Option<Ident>> {
-    // builder属性でない場合
-    if !attr.path().is_ident("builder") {
-        return Ok(None);
+/// `builder(sub_builder)`で型が指定されていない場合に、フィールドの型から内側の
+/// ビルダーの型を決める。
+///
+/// `tls::TlsConfig<T>`の場合は`tls::TlsConfigBuilder<T>`のように、型名に`Builder`を
+/// 付与する。
+fn sub_builder_ty(field_type: &Type) -> Result<Type> {
+    if let Type::Path(TypePath { qself: None, path }) = field_type {
+        let mut path = path.clone();
+        if let Some(last_segment) = path.segments.last_mut() {
+            last_segment.ident = format_ident!("{}Builder", last_segment.ident);
+            return Ok(Type::Path(TypePath { qself: None, path }));
+        }
     }
-    // builder属性内にある名前と値のリストを取得
-    let name_values: CommaPunctuatedNameValues = attr
-        .parse_args_with(Punctuated::parse_terminated)
-        .map_err(|err| {
-            syn::Error::new_spanned(attr, format!("failed to parse builder attribute: {}", err))
-        })?;
-    // builder属性には名前と値のペアが1つだけか確認
-    if name_values.len() != 1 {
-        return Err(syn::Error::new_spanned(
-            attr,
-            "expected `builder(each = \"...\")`",
-        ));
+    Err(Error::new_spanned(
+        field_type,
+        "cannot determine the builder type; use `builder(sub_builder = \"...\")`",
+    ))
+}
+
+/// フィールドに付与されたドキュメントコメントを、各行の先頭の空白を1つ取り除いて
+/// 連結する。
+fn doc_comment(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(MetaNameValue {
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(line),
+                        ..
+                    }),
+                ..
+            }) => Some(line.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 型を、`Option<Vec<String>>`のように表示用の文字列に変換する。
+///
+/// トークンの間に挿入される空白のうち、区切り記号の前後の空白を取り除く。
+fn type_name(ty: &Type) -> String {
+    let mut name = ty.to_token_stream().to_string();
+    for (from, to) in [
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ::", "::"),
+        (":: ", "::"),
+        (" ,", ","),
+        (" ;", ";"),
+        ("& ", "&"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+    ] {
+        name = name.replace(from, to);
+    }
+
+    name
+}
+
+/// `std::collections::HashMap`のように指定された型の、型名より前のモジュールのパスが、
+/// 標準ライブラリでその型を定義しているモジュールの場合は`true`を返す。
+///
+/// モジュールのパスが指定されていない場合は、プレリュードまたは`use`でインポートされた
+/// 標準ライブラリの型とみなす。
+fn is_std_path(modules: &[String], name: &str) -> bool {
+    let Some((root, modules)) = modules.split_first() else {
+        return true;
+    };
+    if !matches!(root.as_str(), "std" | "core" | "alloc") {
+        return false;
     }
-    let name_value = name_values.first().unwrap();
-    // 名前の値のペアについて、名前がeachか確認
-    match name_value.path.is_ident("each") {
-        true => match &name_value.value {
-            Expr::Lit(expr_lit) => match &expr_lit.lit {
-                Lit::Str(value) => Ok(Some(format_ident!("{}", value.value()))),
-                _ => Err(syn::Error::new_spanned(
-                    attr,
+    match (name, modules) {
+        ("Option", [module]) => module == "option",
+        ("Vec", [module]) => module == "vec",
+        // `std::collections::HashMap`及び`std::collections::hash_map::HashMap`
+        (_, [collections]) | (_, [collections, _]) => collections == "collections",
+        _ => false,
+    }
+}
+
+/// フィールドに付与されたすべての`builder`属性を解析する。
+///
+/// 複数の`builder`属性が付与されている場合は、それらを1つの属性として扱う。同じキーが
+/// 複数回指定された場合、または同時に指定できないキーが指定された場合はエラーを返す。
+///
+/// `constant`が`true`の場合は、定数式で構築するビルダーで使用できないキーが指定された
+/// 場合にエラーを返す。
+fn inspect_field_attrs(attrs: &[Attribute], constant: bool) -> Result<FieldAttrs> {
+    let mut field_attrs = FieldAttrs::default();
+    let mut keys = AttrKeys::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                keys.insert(&meta, "each", &[])?;
+                field_attrs.each = Some(inspect_each_attrs(&meta)?);
+            } else if meta.path.is_ident("name") || meta.path.is_ident("rename") {
+                // `rename`は`name`の別名
+                if meta.path.is_ident("name") {
+                    keys.insert(&meta, "name", &["rename"])?;
+                } else {
+                    keys.insert(&meta, "rename", &["name"])?;
+                }
+                field_attrs.name = Some(parse_ident_value(&meta)?);
+            } else if meta.path.is_ident("validate") {
+                keys.insert(&meta, "validate", &[])?;
+                let value: LitStr = meta.value()?.parse()?;
+                field_attrs.validate = Some(value.parse()?);
+            } else if meta.path.is_ident("optional") {
+                keys.insert(&meta, "optional", &["required"])?;
+                field_attrs.optional = Some(true);
+            } else if meta.path.is_ident("required") {
+                keys.insert(&meta, "required", &["optional", "default"])?;
+                field_attrs.optional = Some(false);
+            } else if meta.path.is_ident("private") {
+                keys.insert(&meta, "private", &[])?;
+                field_attrs.private = true;
+            } else if meta.path.is_ident("merge") {
+                keys.insert(&meta, "merge", &[])?;
+                let value: LitStr = meta.value()?.parse()?;
+                if !matches!(value.value().as_str(), "append" | "replace") {
+                    return Err(Error::new_spanned(
+                        value,
+                        "expected `\"append\"` or `\"replace\"`",
+                    ));
+                }
+                field_attrs.merge = Some(value);
+            } else if meta.path.is_ident("sub_builder") {
+                keys.insert(&meta, "sub_builder", &[])?;
+                // `sub_builder`のみの場合は、フィールドの型の名前からビルダーの型を決める
+                field_attrs.sub_builder = Some(if meta.input.peek(Token![=]) {
+                    let value: LitStr = meta.value()?.parse()?;
+                    Some(value.parse()?)
+                } else {
+                    None
+                });
+            } else if meta.path.is_ident("try_setter") {
+                keys.insert(&meta, "try_setter", &[])?;
+                field_attrs.try_setter = true;
+            } else if meta.path.is_ident("setter") {
+                inspect_setter_attrs(&meta, &mut field_attrs.setter, &mut keys)?;
+            } else if meta.path.is_ident("skip") {
+                keys.insert(&meta, "skip", &[])?;
+                // `skip`のみの場合は、フィールドの型の`Default`実装を使用
+                field_attrs.skip = Some(if meta.input.peek(Token![=]) {
+                    let value: LitStr = meta.value()?.parse()?;
+                    FieldDefault::Expr(value.parse()?)
+                } else {
+                    FieldDefault::Trait
+                });
+            } else if meta.path.is_ident("default") {
+                keys.insert(&meta, "default", &["required"])?;
+                // `default`のみの場合は、フィールドの型の`Default`実装を使用
+                field_attrs.default = Some(if meta.input.peek(Token![=]) {
+                    let value: LitStr = meta.value()?.parse()?;
+                    FieldDefault::Expr(value.parse()?)
+                } else {
+                    FieldDefault::Trait
+                });
+            } else {
+                return Err(Error::new_spanned(
+                    &attr.meta,
                     "expected `builder(each = \"...\")`",
-                )),
-            },
-            _ => Err(syn::Error::new_spanned(
-                attr,
-                "expected `builder(each = \"...\")`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    // ビルダーに含めないフィールドには、セッターやビルド時の値に関する指定はできない
+    keys.exclusive("skip", &[])?;
+    // 内側のビルダーを格納するフィールドのセッターは、内側のビルダーを変更する関数を
+    // 受け取るため、値の設定に関する指定はできない
+    keys.exclusive(
+        "sub_builder",
+        &["name", "rename", "private", "validate", "setter(prefix)"],
+    )?;
+    // 定数式で構築するビルダーのメソッドでは、`const fn`ではない関数を呼び出せない
+    if constant {
+        keys.forbid(
+            &[
+                "each",
+                "validate",
+                "try_setter",
+                "setter(into)",
+                "sub_builder",
+            ],
+            "const",
+        )?;
+    }
+
+    Ok(field_attrs)
+}
+
+/// `builder`属性で指定されたキーを記録し、重複したキー及び同時に指定できないキーを
+/// 検出する。
+#[derive(Default)]
+struct AttrKeys {
+    /// 記録したキーと、そのキーの位置。
+    keys: Vec<(String, Span)>,
+}
+
+impl AttrKeys {
+    /// キーを記録する。
+    ///
+    /// キーがすでに記録されている場合、または`conflicts`のいずれかのキーが記録されている
+    /// 場合は、そのキーを指すエラーを返す。
+    fn insert(&mut self, meta: &ParseNestedMeta, key: &str, conflicts: &[&str]) -> Result<()> {
+        if self.contains(key) {
+            return Err(Error::new_spanned(
+                &meta.path,
+                format!("duplicate `builder({})` attribute", key),
+            ));
+        }
+        if let Some(conflict) = conflicts.iter().find(|conflict| self.contains(conflict)) {
+            return Err(Error::new_spanned(
+                &meta.path,
+                format!(
+                    "`builder({})` cannot be used with `builder({})`",
+                    key, conflict
+                ),
+            ));
+        }
+        self.keys.push((key.to_owned(), meta.path.span()));
+        Ok(())
+    }
+
+    /// キーが記録されている場合は`true`を返す。
+    fn contains(&self, key: &str) -> bool {
+        self.keys.iter().any(|(k, _)| k == key)
+    }
+
+    /// `key`が記録されている場合、`key`及び`allowed`以外のキーが記録されていれば、
+    /// そのキーを指すエラーを返す。
+    fn exclusive(&self, key: &str, allowed: &[&str]) -> Result<()> {
+        if !self.contains(key) {
+            return Ok(());
+        }
+        match self
+            .keys
+            .iter()
+            .find(|(k, _)| k != key && !allowed.contains(&k.as_str()))
+        {
+            Some((other, span)) => Err(Error::new(
+                *span,
+                format!(
+                    "`builder({})` cannot be used with `builder({})`",
+                    other, key
+                ),
             )),
-        },
-        false => Err(syn::Error::new_spanned(
-            attr,
-            "expected `builder(each = \"...\")`",
-        )),
+            None => Ok(()),
+        }
+    }
+
+    /// `forbidden`のいずれかのキーが記録されている場合は、そのキーを`key`と同時に指定
+    /// できないことを示すエラーを返す。
+    fn forbid(&self, forbidden: &[&str], key: &str) -> Result<()> {
+        match self
+            .keys
+            .iter()
+            .find(|(k, _)| forbidden.contains(&k.as_str()))
+        {
+            Some((other, span)) => Err(Error::new(
+                *span,
+                format!(
+                    "`builder({})` cannot be used with `builder({})`",
+                    other, key
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `each = "arg"`、または`each(name = "arg", item = "Type")`のような要素を追加する
+/// メソッドの指定を解析する。
+fn inspect_each_attrs(meta: &ParseNestedMeta) -> Result<EachAttrs> {
+    if meta.input.peek(Token![=]) {
+        return Ok(EachAttrs {
+            name: parse_ident_value(meta)?,
+            item: None,
+        });
+    }
+    let (mut name, mut item) = (None, None);
+    let mut keys = AttrKeys::default();
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            keys.insert(&meta, "each(name)", &[])?;
+            name = Some(parse_ident_value(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("item") {
+            keys.insert(&meta, "each(item)", &[])?;
+            let value: LitStr = meta.value()?.parse()?;
+            item = Some(value.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `each(name = \"...\", item = \"...\")`"))
+        }
+    })?;
+    match name {
+        Some(name) => Ok(EachAttrs { name, item }),
+        None => Err(meta.error("missing `name` in `builder(each(...))`")),
+    }
+}
+
+/// `setter(into, strip_option = false)`のようなセッターの形式を解析する。
+fn inspect_setter_attrs(
+    meta: &ParseNestedMeta,
+    setter: &mut SetterAttrs,
+    keys: &mut AttrKeys,
+) -> Result<()> {
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("into") {
+            keys.insert(&meta, "setter(into)", &[])?;
+            setter.into = Some(parse_flag(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("strip_option") {
+            keys.insert(&meta, "setter(strip_option)", &[])?;
+            setter.strip_option = Some(parse_flag(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("prefix") {
+            keys.insert(&meta, "setter(prefix)", &[])?;
+            setter.prefix = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error(
+                "expected `setter(into)`, `setter(strip_option = false)` or `setter(prefix = \"...\")`",
+            ))
+        }
+    })
+}
+
+/// `serde`属性で`key = "..."`または`key(serialize = "...", deserialize = "...")`の
+/// 形式で指定された値を、シリアライズ用とデシリアライズ用の組で返す。
+///
+/// `serde`属性はserdeのderiveマクロが解析するため、それ以外の内容及び解析できない内容は
+/// 無視する。
+fn serde_attr_pair(attrs: &[Attribute], key: &str) -> (Option<LitStr>, Option<LitStr>) {
+    let mut serialize = None;
+    let mut deserialize = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident(key) {
+                return skip_nested_meta(&meta);
+            }
+            if meta.input.peek(Token![=]) {
+                let value: LitStr = meta.value()?.parse()?;
+                serialize = Some(value.clone());
+                deserialize = Some(value);
+                Ok(())
+            } else {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("serialize") {
+                        serialize = Some(meta.value()?.parse()?);
+                        Ok(())
+                    } else if meta.path.is_ident("deserialize") {
+                        deserialize = Some(meta.value()?.parse()?);
+                        Ok(())
+                    } else {
+                        skip_nested_meta(&meta)
+                    }
+                })
+            }
+        });
+    }
+
+    (serialize, deserialize)
+}
+
+/// `serde(rename_all = "...")`のような属性で指定された、フィールド名の変換規則を返す。
+fn serde_rename_all(attrs: &[Attribute], key: &str) -> Result<SerdeRenameAll> {
+    let (serialize, deserialize) = serde_attr_pair(attrs, key);
+    Ok(SerdeRenameAll {
+        serialize: serialize.as_ref().map(RenameRule::parse).transpose()?,
+        deserialize: deserialize.as_ref().map(RenameRule::parse).transpose()?,
+    })
+}
+
+/// `builder(serde)`が指定された場合に、フィールドに付与された`serde`属性のうち、
+/// ビルダーのフィールドにも付与する内容を返す。
+///
+/// ビルダーが読み込む形式を構造体と揃えるため、`alias`及び`skip`などはビルダーの
+/// フィールドにも付与する。`rename`は名前を決めるときに、`skip_serializing_if`は値が
+/// 設定されていないフィールドを書き出さないことで扱う。`with`や`default`のように
+/// フィールドの型の値を扱う指定は、ビルダーの`Option`型のフィールドには適用できない
+/// ため、エラーを返す。
+fn serde_field_attrs(attrs: &[Attribute]) -> Result<Vec<TokenStream2>> {
+    let mut serde_attrs = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            let path = &meta.path;
+            if path.is_ident("rename") || path.is_ident("skip_serializing_if") {
+                skip_nested_meta(&meta)
+            } else if path.is_ident("alias") {
+                let alias: LitStr = meta.value()?.parse()?;
+                serde_attrs.push(quote! { alias = #alias });
+                Ok(())
+            } else if path.is_ident("skip")
+                || path.is_ident("skip_serializing")
+                || path.is_ident("skip_deserializing")
+            {
+                serde_attrs.push(quote! { #path });
+                Ok(())
+            } else {
+                Err(meta.error(format!(
+                    "`serde({})` is not supported with `builder(serde)`",
+                    path.to_token_stream()
+                )))
+            }
+        })?;
+    }
+
+    Ok(serde_attrs)
+}
+
+/// `key = value`または`key(...)`のような属性の内容を読み飛ばす。
+fn skip_nested_meta(meta: &ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+        Ok(())
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|meta| skip_nested_meta(&meta))
+    } else {
+        Ok(())
+    }
+}
+
+/// `into`または`into = false`のようなフラグを解析する。
+///
+/// 名前だけが指定された場合は`true`を返す。
+fn parse_flag(meta: &ParseNestedMeta) -> Result<bool> {
+    if meta.input.peek(Token![=]) {
+        let value: LitBool = meta.value()?.parse()?;
+        Ok(value.value)
+    } else {
+        Ok(true)
+    }
+}
+
+/// `each = "arg"`のような名前と値のペアについて、値の文字列リテラルを識別子として解析する。
+fn parse_ident_value(meta: &ParseNestedMeta) -> Result<Ident> {
+    let value: LitStr = meta.value()?.parse()?;
+    value.parse()
+}
+
+/// 構造体に付与されたすべての`builder`属性を解析する。
+fn inspect_struct_attrs(attrs: &[Attribute]) -> Result<StructAttrs> {
+    let mut struct_attrs = StructAttrs::default();
+    let mut pattern_lit: Option<LitStr> = None;
+    let mut keys = AttrKeys::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("typestate") {
+                keys.insert(&meta, "typestate", &["serde"])?;
+                struct_attrs.typestate = true;
+                Ok(())
+            } else if meta.path.is_ident("const") {
+                keys.insert(&meta, "const", &[])?;
+                struct_attrs.constant = true;
+                Ok(())
+            } else if meta.path.is_ident("default") {
+                keys.insert(&meta, "default", &[])?;
+                struct_attrs.default = Some(meta.path);
+                Ok(())
+            } else if meta.path.is_ident("validate") {
+                keys.insert(&meta, "validate", &[])?;
+                let value: LitStr = meta.value()?.parse()?;
+                struct_attrs.validate = Some(value.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("error") {
+                keys.insert(&meta, "error", &[])?;
+                let value: LitStr = meta.value()?.parse()?;
+                struct_attrs.error = Some(value.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("setter") {
+                inspect_setter_attrs(&meta, &mut struct_attrs.setter, &mut keys)
+            } else if meta.path.is_ident("name") {
+                keys.insert(&meta, "name", &[])?;
+                struct_attrs.name = Some(parse_ident_value(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("constructor") {
+                keys.insert(&meta, "constructor", &[])?;
+                struct_attrs.constructor = Some(parse_ident_value(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("build_fn") {
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("name") {
+                        keys.insert(&meta, "build_fn(name)", &[])?;
+                        struct_attrs.build_fn = Some(parse_ident_value(&meta)?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `build_fn(name = \"...\")`"))
+                    }
+                })
+            } else if meta.path.is_ident("serde") {
+                keys.insert(&meta, "serde", &["typestate"])?;
+                // 生成したコードはserdeに依存するため、フィーチャーで明示的に有効にする
+                if !cfg!(feature = "serde") {
+                    return Err(meta
+                        .error("`builder(serde)` requires the `serde` feature of derive_builder"));
+                }
+                struct_attrs.serde = true;
+                Ok(())
+            } else if meta.path.is_ident("no_std") {
+                keys.insert(&meta, "no_std", &[])?;
+                let mut alloc = false;
+                if meta.input.peek(syn::token::Paren) {
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("alloc") {
+                            alloc = true;
+                            Ok(())
+                        } else {
+                            Err(meta.error("expected `no_std(alloc)`"))
+                        }
+                    })?;
+                }
+                struct_attrs.no_std = Some(alloc);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|meta| {
+                    struct_attrs.derives.push(meta.path);
+                    Ok(())
+                })
+            } else if meta.path.is_ident("struct_attrs") {
+                let content;
+                parenthesized!(content in meta.input);
+                struct_attrs
+                    .attrs
+                    .extend(content.parse_terminated(Meta::parse, Token![,])?);
+                Ok(())
+            } else if meta.path.is_ident("vis") {
+                keys.insert(&meta, "vis", &[])?;
+                let value: LitStr = meta.value()?.parse()?;
+                struct_attrs.vis = Some(value.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("pattern") {
+                keys.insert(&meta, "pattern", &[])?;
+                let value: LitStr = meta.value()?.parse()?;
+                struct_attrs.pattern = match value.value().as_str() {
+                    "mutable" => BuilderPattern::Mutable,
+                    "owned" => BuilderPattern::Owned,
+                    "immutable" => BuilderPattern::Immutable,
+                    _ => {
+                        return Err(Error::new_spanned(
+                            value,
+                            "expected `\"mutable\"`, `\"owned\"` or `\"immutable\"`",
+                        ))
+                    }
+                };
+                pattern_lit = Some(value);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported builder attribute"))
+            }
+        })?;
+    }
+    // 型状態を持つビルダーのセッターは、ビルダーの型を変更するため`self`を受け取る
+    if let Some(pattern_lit) = &pattern_lit {
+        if struct_attrs.typestate && struct_attrs.pattern != BuilderPattern::Owned {
+            return Err(Error::new_spanned(
+                pattern_lit,
+                "`builder(typestate)` only supports the owned pattern",
+            ));
+        }
+    }
+    // 定数式で構築するビルダーは、値を変更した`self`を返す`const fn`のセッターを持つ
+    // ため、検証関数などの`const fn`ではない関数を呼び出す指定はできない
+    keys.exclusive(
+        "const",
+        &[
+            "name",
+            "constructor",
+            "build_fn(name)",
+            "vis",
+            "pattern",
+            "setter(strip_option)",
+            "setter(prefix)",
+            "no_std",
+        ],
+    )?;
+    if struct_attrs.constant {
+        if let Some(pattern_lit) = pattern_lit {
+            if struct_attrs.pattern != BuilderPattern::Owned {
+                return Err(Error::new_spanned(
+                    pattern_lit,
+                    "`builder(const)` only supports the owned pattern",
+                ));
+            }
+        }
+        struct_attrs.pattern = BuilderPattern::Owned;
+    }
+    // 型状態を持つビルダーが`Default`を実装すると、必須フィールドに値が設定された状態の
+    // ビルダーを値を設定せずに作成できてしまう
+    if struct_attrs.typestate {
+        if let Some(path) = struct_attrs
+            .derives
+            .iter()
+            .find(|path| path.is_ident("Default"))
+        {
+            return Err(Error::new_spanned(
+                path,
+                "`builder(derive(Default))` cannot be used with `builder(typestate)`",
+            ));
+        }
+    }
+
+    Ok(struct_attrs)
+}
+
+/// ジェネリックパラメーターを、型の引数として指定する形式に変換する。
+///
+/// `<'a, T: Clone, const N: usize>`の場合は、`'a`、`T`及び`N`を返す。
+fn generic_args(generics: &Generics) -> Vec<TokenStream2> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        })
+        .collect()
+}
+
+/// `CommandBuilder`のようなキャメルケースの名前を、`command_builder`のようなスネークケース
+/// に変換する。
+///
+/// `HTTPGet`のように大文字が続く部分は1つの単語として扱い、`http_get`に変換する。
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i != 0 {
+            // 小文字や数字の後の大文字、または大文字の後で小文字が続く大文字から単語を始める
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(ch.to_lowercase());
     }
+
+    snake
 }
 
-/// `foo = "a", bar = "b"`のような、カンマで区切られた名前と値のリスト
-pub(crate) type CommaPunctuatedNameValues = Punctuated<MetaNameValue, Token![,]>;
+/// `current_dir`のようなスネークケースの名前を、`CurrentDir`のようなキャメルケースに
+/// 変換する。
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
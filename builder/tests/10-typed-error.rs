@@ -0,0 +1,44 @@
+// The build function reports every required field that has not been given a
+// value, not only the first one, through an error type generated for each
+// builder.
+//
+// For a struct named `Command` the macro generates `CommandBuilderError`, an
+// enum implementing Debug, Display and std::error::Error, so that callers can
+// tell which fields are missing without matching on error strings:
+//
+//     enum CommandBuilderError {
+//         MissingField(&'static str),
+//         MissingFields(Vec<&'static str>),
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: String,
+}
+
+fn main() {
+    let err = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(err, CommandBuilderError::MissingField("current_dir"));
+    assert_eq!(err.to_string(), "current_dir is not provided");
+
+    let err = Command::builder().build().unwrap_err();
+    assert_eq!(
+        err,
+        CommandBuilderError::MissingFields(vec!["executable", "current_dir"])
+    );
+    assert_eq!(
+        err.to_string(),
+        "executable, current_dir are not provided"
+    );
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert!(err.source().is_none());
+}
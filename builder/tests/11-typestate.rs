@@ -0,0 +1,70 @@
+// With #[builder(typestate)] on the struct, whether each required field has
+// been given a value is tracked in the type of the builder rather than checked
+// at runtime.
+//
+// The builder carries one type parameter per required field. It starts out as
+// `Unset` and the setter of that field changes it to `Set`, so the build
+// function is only available once every required field has been set and it
+// returns the struct itself instead of a Result. Fields of type Option and Vec
+// stay unconstrained and may be set in any order, or not at all.
+//
+// Since the setters change the type of the builder, they take the builder by
+// value and return the new builder:
+//
+//     impl<__State0, __State1> CommandBuilder<__State0, __State1> {
+//         fn executable(self, executable: String) -> CommandBuilder<command_builder::Set, __State1> {
+//             ...
+//         }
+//     }
+//
+// The type parameters are numbered, so fields whose names would collide when
+// converted to CamelCase, or raw identifiers, need no special treatment.
+
+#![allow(non_snake_case)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Option<String>,
+    current_dir: String,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Grid {
+    x_y: u32,
+    x__y: u32,
+    r#loop: bool,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct HTTPConfig {
+    port: u16,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .arg("--release".to_owned())
+        .executable("cargo".to_owned())
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert!(command.env.is_none());
+    assert_eq!(command.current_dir, "..");
+
+    let grid = Grid::builder().r#loop(true).x__y(2).x_y(1).build();
+    assert_eq!((grid.x_y, grid.x__y, grid.r#loop), (1, 2, true));
+
+    // The state module is named after the builder, with acronyms kept whole.
+    let builder: HTTPConfigBuilder<http_config_builder::Set> = HTTPConfig::builder().port(80);
+    assert_eq!(builder.build().port, 80);
+}
@@ -0,0 +1,17 @@
+// Forgetting to set a required field of a typestate builder is a compile error
+// rather than a runtime error, because the build function does not exist until
+// every required field has been set.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command {
+    executable: String,
+    args: Vec<String>,
+    current_dir: String,
+}
+
+fn main() {
+    let _command = Command::builder().executable("cargo".to_owned()).build();
+}
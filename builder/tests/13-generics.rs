@@ -0,0 +1,78 @@
+// Generate a builder for a struct with generic parameters.
+//
+// The type, lifetime and const parameters of the struct, together with its
+// where-clause, need to be carried through to the builder struct, its impl
+// block and the builder function.
+//
+//     struct RequestBuilder<'a, T: Clone, const N: usize>
+//     where
+//         T: Debug,
+//     {
+//         ...
+//     }
+//
+//     impl<'a, T: Clone, const N: usize> RequestBuilder<'a, T, N>
+//     where
+//         T: Debug,
+//     {
+//         ...
+//     }
+//
+// Resources:
+//
+//   - Splitting generics into the pieces needed by an impl block:
+//     https://docs.rs/syn/2.0/syn/struct.Generics.html#method.split_for_impl
+
+use derive_builder::Builder;
+use std::fmt::Debug;
+
+#[derive(Builder)]
+pub struct Request<'a, T: Clone, const N: usize>
+where
+    T: Debug,
+{
+    path: &'a str,
+    body: T,
+    #[builder(each = "header")]
+    headers: Vec<(&'a str, &'a str)>,
+    timeout: Option<u64>,
+    retries: [u8; N],
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Response<'a, T = String>
+where
+    T: Debug,
+{
+    status: u16,
+    body: T,
+    reason: Option<&'a str>,
+}
+
+fn main() {
+    let path = String::from("/index.html");
+    let request: Request<'_, Vec<u8>, 2> = Request::builder()
+        .path(&path)
+        .body(vec![1, 2, 3])
+        .header(("Accept", "text/html"))
+        .retries([1, 2])
+        .build()
+        .unwrap();
+
+    assert_eq!(request.path, "/index.html");
+    assert_eq!(request.body, vec![1, 2, 3]);
+    assert_eq!(request.headers, vec![("Accept", "text/html")]);
+    assert!(request.timeout.is_none());
+    assert_eq!(request.retries, [1, 2]);
+
+    let response: Response = Response::builder()
+        .reason("OK")
+        .status(200)
+        .body("hello".to_owned())
+        .build();
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, "hello");
+    assert_eq!(response.reason, Some("OK"));
+}
@@ -0,0 +1,92 @@
+// Generate builders for tuple structs and enums.
+//
+// The fields of a tuple struct have no names, so the setters are named after
+// their position (`_0`, `_1`, ...) unless a name is given explicitly with
+// #[builder(name = "...")].
+//
+// For an enum, generate one builder per variant that has named or unnamed
+// fields. The builder of the `Circle` variant of `Shape` is `ShapeCircleBuilder`
+// and is created with `Shape::circle_builder()`. Unit variants get no builder.
+// A run of capital letters is one word, so the constructor for `HTTPGet` is
+// `http_get_builder()`.
+//
+// In both cases a struct expression with integer member names can be used to
+// construct the value, which avoids treating tuple fields specially:
+//
+//     Address { 0: ..., 1: ... }
+//     Shape::Polygon { 0: ... }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Address(#[builder(name = "host")] String, u16, Option<String>);
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+        label: Option<String>,
+    },
+    Polygon(#[builder(each = "point")] Vec<(f64, f64)>),
+    Empty,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub enum Request {
+    HTTPGet { url: String },
+    IOWait(u64),
+}
+
+fn main() {
+    let address = Address::builder()
+        .host("localhost".to_owned())
+        ._1(8080)
+        .build()
+        .unwrap();
+    assert_eq!(address.0, "localhost");
+    assert_eq!(address.1, 8080);
+    assert!(address.2.is_none());
+
+    let err = Address::builder()._2("http".to_owned()).build().err();
+    assert_eq!(
+        err,
+        Some(AddressBuilderError::MissingFields(vec!["host", "_1"]))
+    );
+
+    let circle = Shape::circle_builder().radius(1.5).build().unwrap();
+    assert_eq!(
+        circle,
+        Shape::Circle {
+            radius: 1.5,
+            label: None,
+        }
+    );
+
+    let polygon = Shape::polygon_builder()
+        .point((0.0, 0.0))
+        .point((1.0, 0.0))
+        .point((0.0, 1.0))
+        .build()
+        .unwrap();
+    assert_eq!(
+        polygon,
+        Shape::Polygon(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)])
+    );
+
+    let err = Shape::circle_builder().build().err();
+    assert_eq!(err, Some(ShapeCircleBuilderError::MissingField("radius")));
+    assert_ne!(Shape::Empty, polygon);
+
+    let get = Request::http_get_builder()
+        .url("https://example.com".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(
+        get,
+        Request::HTTPGet {
+            url: "https://example.com".to_owned(),
+        }
+    );
+    let wait = Request::io_wait_builder()._0(5).build().unwrap();
+    assert_eq!(wait, Request::IOWait(5));
+}
@@ -0,0 +1,76 @@
+// Fields with #[builder(default)] fall back to Default::default() when the
+// caller does not set them, and fields with #[builder(default = "...")] fall
+// back to the given expression.
+//
+// With #[builder(default)] on the struct itself, the build function starts out
+// from the value returned by the Default impl of the struct and only overrides
+// the fields that have been set on the builder, or that have a default of their
+// own.
+//
+// Fields with a default are no longer required, so they are not reported as
+// missing by the build function.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(default = "8080")]
+    port: u16,
+    #[builder(default)]
+    workers: usize,
+    name: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(default)]
+pub struct Client {
+    host: String,
+    #[builder(default = "Some(30)")]
+    timeout: Option<u64>,
+    user_agent: Option<String>,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client {
+            host: "localhost".to_owned(),
+            timeout: None,
+            user_agent: Some("workshop".to_owned()),
+        }
+    }
+}
+
+fn main() {
+    let server = Server::builder().host("0.0.0.0".to_owned()).build().unwrap();
+    assert_eq!(server.host, "0.0.0.0");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, 0);
+    assert!(server.name.is_none());
+
+    let server = Server::builder()
+        .host("0.0.0.0".to_owned())
+        .port(80)
+        .workers(4)
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 80);
+    assert_eq!(server.workers, 4);
+
+    let err = Server::builder().port(80).build().err().unwrap();
+    assert_eq!(err, ServerBuilderError::MissingField("host"));
+
+    let client = Client::builder().build().unwrap();
+    assert_eq!(client.host, "localhost");
+    assert_eq!(client.timeout, Some(30));
+    assert_eq!(client.user_agent.as_deref(), Some("workshop"));
+
+    let client = Client::builder()
+        .host("example.com".to_owned())
+        .timeout(5)
+        .build()
+        .unwrap();
+    assert_eq!(client.host, "example.com");
+    assert_eq!(client.timeout, Some(5));
+    assert_eq!(client.user_agent.as_deref(), Some("workshop"));
+}
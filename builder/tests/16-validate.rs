@@ -0,0 +1,137 @@
+// Check invariants of the built value with validation hooks.
+//
+// #[builder(validate = "path::to::fn")] on a field calls the function with a
+// reference to the value of that field, and on the struct calls it with a
+// reference to the assembled struct. A hook returns Result<(), E>, and an error
+// makes the build function fail.
+//
+// By default the build function returns the generated error type, which can be
+// created from a String and reports it as `ValidationFailed`. With
+// #[builder(error = "...")] on the struct, the build function returns the given
+// error type instead. That type needs to implement From for the generated error
+// type, for the missing fields, as well as for the errors returned by the hooks.
+//
+// Field hooks run before any value is moved out of the builder, so after a
+// failed validation the builder still holds every value and the offending
+// field can be corrected before building again.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "check_server")]
+pub struct Server {
+    host: String,
+    #[builder(validate = "check_port", default = "8080")]
+    port: u16,
+}
+
+fn check_port(port: &u16) -> Result<(), String> {
+    if *port < 1024 {
+        return Err(format!("port {} is reserved", port));
+    }
+    Ok(())
+}
+
+fn check_server(server: &Server) -> Result<(), String> {
+    if server.host.is_empty() {
+        return Err("host is empty".to_owned());
+    }
+    Ok(())
+}
+
+#[derive(Builder, Default)]
+#[builder(default)]
+pub struct Pool {
+    name: String,
+    #[builder(validate = "check_workers")]
+    workers: u32,
+}
+
+fn check_workers(workers: &u32) -> Result<(), String> {
+    if *workers == 0 {
+        return Err("workers must not be zero".to_owned());
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum ConfigError {
+    Builder(ClientBuilderError),
+    OutOfRange(u64),
+}
+
+impl From<ClientBuilderError> for ConfigError {
+    fn from(err: ClientBuilderError) -> Self {
+        ConfigError::Builder(err)
+    }
+}
+
+#[derive(Builder)]
+#[builder(error = "ConfigError")]
+pub struct Client {
+    #[builder(validate = "check_timeout")]
+    timeout: u64,
+}
+
+fn check_timeout(timeout: &u64) -> Result<(), ConfigError> {
+    if *timeout > 60 {
+        return Err(ConfigError::OutOfRange(*timeout));
+    }
+    Ok(())
+}
+
+fn main() {
+    let server = Server::builder().host("localhost".to_owned()).build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+
+    let err = Server::builder()
+        .host("localhost".to_owned())
+        .port(80)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        ServerBuilderError::ValidationFailed("port 80 is reserved".to_owned())
+    );
+    assert_eq!(err.to_string(), "port 80 is reserved");
+
+    let err = Server::builder().host(String::new()).build().err().unwrap();
+    assert_eq!(err.to_string(), "host is empty");
+
+    let client = Client::builder().timeout(30).build().unwrap();
+    assert_eq!(client.timeout, 30);
+
+    let err = Client::builder().timeout(90).build().err();
+    assert_eq!(err, Some(ConfigError::OutOfRange(90)));
+
+    let err = Client::builder().build().err();
+    assert_eq!(
+        err,
+        Some(ConfigError::Builder(ClientBuilderError::MissingField(
+            "timeout"
+        )))
+    );
+
+    // A failed field validation keeps the values set so far.
+    let mut builder = Server::builder();
+    builder.host("localhost".to_owned()).port(80);
+    assert_eq!(
+        builder.build().err().unwrap().to_string(),
+        "port 80 is reserved"
+    );
+    let server = builder.port(8443).build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8443);
+
+    let mut builder = Pool::builder();
+    builder.name("db".to_owned());
+    assert_eq!(
+        builder.build().err().unwrap().to_string(),
+        "workers must not be zero"
+    );
+    builder.workers(4);
+    let pool = builder.build().unwrap();
+    assert_eq!((pool.name.as_str(), pool.workers), ("db", 4));
+}
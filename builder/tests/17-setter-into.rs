@@ -0,0 +1,61 @@
+// Control the argument types of the setters.
+//
+// With #[builder(setter(into))] on a field, its setter accepts anything that
+// converts into the field type, so `.name("x")` works for a String field. On
+// the struct, the same attribute applies to every field that does not say
+// otherwise with #[builder(setter(into = false))].
+//
+// The setter of an Option<T> field accepts T by default. With
+// #[builder(setter(strip_option = false))] it accepts Option<T> instead, so
+// that the field can also be set explicitly to None.
+//
+//     fn name(&mut self, name: impl Into<String>) -> &mut Self
+//     fn proxy(&mut self, proxy: Option<String>) -> &mut Self
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Client {
+    #[builder(setter(into))]
+    name: String,
+    #[builder(setter(strip_option = false))]
+    proxy: Option<String>,
+    #[builder(setter(into, strip_option = false))]
+    user_agent: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(setter(into))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(setter(into = false))]
+    timeout: u64,
+}
+
+fn main() {
+    let client = Client::builder()
+        .name("workshop")
+        .proxy(None)
+        .user_agent(Some("curl".to_owned()))
+        .build()
+        .unwrap();
+    assert_eq!(client.name, "workshop");
+    assert!(client.proxy.is_none());
+    assert_eq!(client.user_agent.as_deref(), Some("curl"));
+
+    let command = Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg("--release")
+        .current_dir("..")
+        .timeout(60)
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.timeout, 60);
+}
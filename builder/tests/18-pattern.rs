@@ -0,0 +1,55 @@
+// Select how the builder is passed around with
+// #[builder(pattern = "mutable" | "owned" | "immutable")].
+//
+//   - mutable (the default): setters take and return `&mut Self`, and the
+//     build function moves the values out of the builder, leaving it empty.
+//
+//   - owned: setters take and return `Self`, and the build function consumes
+//     the builder.
+//
+//   - immutable: setters take `&self` and return a new builder with the value
+//     set, and the build function clones the values. One preconfigured builder
+//     can be used to build any number of values. The builder implements Clone,
+//     which requires every field type to implement Clone.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Fixture {
+    name: String,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+    owner: Option<String>,
+}
+
+fn main() {
+    let builder = Command::builder().executable("cargo".to_owned());
+    let command = builder.arg("build".to_owned()).build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+
+    let base = Fixture::builder()
+        .name("fixture".to_owned())
+        .tag("test".to_owned());
+
+    let first = base.owner("alice".to_owned()).build().unwrap();
+    let second = base.tag("slow".to_owned()).build().unwrap();
+    let third = base.clone().build().unwrap();
+
+    assert_eq!(first.name, "fixture");
+    assert_eq!(first.tags, vec!["test"]);
+    assert_eq!(first.owner.as_deref(), Some("alice"));
+    assert_eq!(second.tags, vec!["test", "slow"]);
+    assert!(second.owner.is_none());
+    assert_eq!(third.tags, vec!["test"]);
+    assert_eq!(base.build().unwrap().name, "fixture");
+}
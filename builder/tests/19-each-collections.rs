@@ -0,0 +1,72 @@
+// The `each` attribute works for more than Vec. Sets and sequences such as
+// HashSet, BTreeSet and VecDeque get a one-argument setter that inserts an
+// item, and maps such as HashMap and BTreeMap get a setter that takes a key and
+// a value.
+//
+// Any other collection that implements Extend and Default can be used by naming
+// the item type explicitly with #[builder(each(name = "...", item = "..."))].
+//
+// As with Vec, collection fields that are never set build as empty
+// collections.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+#[derive(Default)]
+pub struct Queue<T> {
+    items: Vec<T>,
+}
+
+impl<T> Extend<T> for Queue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+#[derive(Builder)]
+pub struct Request {
+    url: String,
+    #[builder(each = "header")]
+    headers: HashMap<String, String>,
+    #[builder(each = "param")]
+    params: BTreeMap<String, u32>,
+    #[builder(each = "tag")]
+    tags: BTreeSet<String>,
+    #[builder(each = "retry")]
+    retries: VecDeque<u64>,
+    #[builder(each(name = "job", item = "u8"))]
+    jobs: Queue<u8>,
+}
+
+fn main() {
+    let request = Request::builder()
+        .url("https://example.com".to_owned())
+        .header("Accept".to_owned(), "text/html".to_owned())
+        .header("Host".to_owned(), "example.com".to_owned())
+        .param("page".to_owned(), 2)
+        .tag("b".to_owned())
+        .tag("a".to_owned())
+        .tag("b".to_owned())
+        .retry(100)
+        .retry(200)
+        .job(1)
+        .job(2)
+        .build()
+        .unwrap();
+
+    assert_eq!(request.url, "https://example.com");
+    assert_eq!(request.headers.len(), 2);
+    assert_eq!(request.headers["Host"], "example.com");
+    assert_eq!(request.params["page"], 2);
+    assert_eq!(request.tags.into_iter().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(request.retries, [100, 200]);
+    assert_eq!(request.jobs.items, [1, 2]);
+
+    let request = Request::builder()
+        .url("https://example.com".to_owned())
+        .build()
+        .unwrap();
+    assert!(request.headers.is_empty());
+    assert!(request.tags.is_empty());
+    assert!(request.jobs.items.is_empty());
+}
@@ -0,0 +1,64 @@
+// Option and Vec fields are recognized even when they are written with their
+// full path, such as std::option::Option<T>, ::core::option::Option<T> or
+// alloc::vec::Vec<T>. Collections under std::collections are recognized the
+// same way.
+//
+// A type alias hides the type from the macro, so the field is treated as a
+// required field. Use #[builder(optional)] to let such a field fall back to
+// Default::default() when it is not set. Use #[builder(required)] to make an
+// Option or Vec field required even though it has a natural empty value.
+
+extern crate alloc;
+
+use derive_builder::Builder;
+
+type MaybePort = Option<u16>;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    user: std::option::Option<String>,
+    password: ::core::option::Option<String>,
+    #[builder(each = "alias")]
+    aliases: alloc::vec::Vec<String>,
+    #[builder(each = "label")]
+    labels: ::std::collections::BTreeMap<String, String>,
+    #[builder(optional)]
+    port: MaybePort,
+    #[builder(required)]
+    workers: Option<usize>,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .user("admin".to_owned())
+        .alias("local".to_owned())
+        .label("env".to_owned(), "dev".to_owned())
+        .workers(4)
+        .build()
+        .unwrap();
+
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.user.as_deref(), Some("admin"));
+    assert_eq!(server.password, None);
+    assert_eq!(server.aliases, ["local"]);
+    assert_eq!(server.labels["env"], "dev");
+    assert_eq!(server.port, None);
+    assert_eq!(server.workers, Some(4));
+
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .port(Some(8080))
+        .workers(1)
+        .build()
+        .unwrap();
+    assert_eq!(server.port, Some(8080));
+
+    let err = Server::builder()
+        .host("localhost".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "workers is not provided");
+}
@@ -0,0 +1,54 @@
+// Every #[builder(...)] attribute on a field or on the struct is read, no
+// matter where it appears among the other attributes, and the keys of all of
+// them are merged. Doc comments and attributes meant for other derives can come
+// first.
+//
+// A single attribute can also hold several keys at once, separated by commas.
+// `rename` is accepted as another spelling of `name`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+/// A command to run.
+#[builder(setter(into))]
+#[allow(dead_code)]
+#[builder(pattern = "owned")]
+pub struct Command {
+    /// The program to run.
+    #[builder(rename = "program")]
+    executable: String,
+    /// The arguments passed to the program.
+    #[allow(unused)]
+    #[builder(each = "arg")]
+    #[builder(setter(into = false))]
+    args: Vec<String>,
+    #[builder(default = "\"/tmp\".to_owned()", validate = "check_dir")]
+    current_dir: String,
+}
+
+fn check_dir(dir: &String) -> Result<(), String> {
+    if dir.starts_with('/') {
+        Ok(())
+    } else {
+        Err(format!("{} is not absolute", dir))
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .program("cargo")
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build"]);
+    assert_eq!(command.current_dir, "/tmp");
+
+    let err = Command::builder()
+        .program("cargo")
+        .current_dir("src")
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "src is not absolute");
+}
@@ -0,0 +1,27 @@
+// Keys that are given more than once, even across separate #[builder(...)]
+// attributes, and keys that contradict each other are reported at the
+// offending key.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    #[builder(each = "argument")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(required, default)]
+    port: Option<u16>,
+}
+
+#[derive(Builder)]
+#[builder(setter(into))]
+#[builder(setter(into = false))]
+pub struct Client {
+    host: String,
+}
+
+fn main() {}
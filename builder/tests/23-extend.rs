@@ -0,0 +1,42 @@
+// A field with #[builder(each = "...")] keeps the setter that replaces the
+// whole collection, and also gets an extend_<field> method that appends every
+// item of an IntoIterator. For maps the items are (key, value) pairs.
+//
+// When the `each` name is the same as the field name, the per-item method takes
+// that name and the whole-collection setter is not generated.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .args(vec!["build".to_owned()])
+        .arg("--release".to_owned())
+        .extend_args(["--locked".to_owned(), "--offline".to_owned()])
+        .env("RUST_LOG".to_owned(), "debug".to_owned())
+        .extend_env([("CARGO_HOME".to_owned(), "/tmp".to_owned())])
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, ["build", "--release", "--locked", "--offline"]);
+    assert_eq!(command.env.len(), 2);
+
+    // The whole-collection setter replaces what was added before.
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("test".to_owned())
+        .args(vec!["build".to_owned()])
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["build"]);
+}
@@ -0,0 +1,14 @@
+// The builder cannot have two methods with the same name. A clash between a
+// setter, an `each` method or an extend_<field> method is reported at the name
+// that causes it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    arg: String,
+}
+
+fn main() {}
@@ -0,0 +1,63 @@
+// The generated builder, its setters, its build function, its error type and
+// the builder() constructor have the same visibility as the struct, so a
+// builder derived for a pub struct can be used outside of the defining module
+// or crate. The same holds for the marker module of a typestate builder.
+//
+// #[builder(vis = "...")] on the struct overrides the visibility of the
+// builder. #[builder(private)] on a field keeps the setters of that field
+// private to the defining module.
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Debug)]
+    #[non_exhaustive]
+    pub struct Server {
+        pub host: String,
+        pub port: u16,
+        #[builder(private, default = "1")]
+        pub workers: usize,
+    }
+
+    impl ServerBuilder {
+        pub fn single_threaded(&mut self) -> &mut Self {
+            self.workers(1)
+        }
+    }
+
+    #[derive(Builder)]
+    #[builder(vis = "pub(crate)")]
+    pub struct Client {
+        pub url: String,
+    }
+
+    #[derive(Builder)]
+    #[builder(typestate)]
+    pub struct Proxy {
+        pub upstream: String,
+    }
+}
+
+use config::{ClientBuilder, Proxy, Server, ServerBuilder, ServerBuilderError};
+
+fn main() {
+    let mut builder: ServerBuilder = Server::builder();
+    let server = builder
+        .host("localhost".to_owned())
+        .port(8080)
+        .single_threaded()
+        .build()
+        .unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.workers, 1);
+
+    let err: ServerBuilderError = Server::builder().build().unwrap_err();
+    assert_eq!(err, ServerBuilderError::MissingFields(vec!["host", "port"]));
+
+    let mut builder: ClientBuilder = config::Client::builder();
+    let client = builder.url("https://example.com".to_owned()).build().unwrap();
+    assert_eq!(client.url, "https://example.com");
+
+    let proxy = Proxy::builder().upstream("localhost".to_owned()).build();
+    assert_eq!(proxy.upstream, "localhost");
+}
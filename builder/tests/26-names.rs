@@ -0,0 +1,76 @@
+// The names of the generated items can be changed to avoid clashes with
+// existing methods or to follow the conventions of another API.
+//
+//   - #[builder(name = "...")] names the builder type.
+//   - #[builder(constructor = "...")] names the function that creates it.
+//   - #[builder(build_fn(name = "..."))] names the build function.
+//   - #[builder(setter(prefix = "..."))] adds a prefix to every setter, and can
+//     also be given on a single field.
+//
+// The error type is named after the builder, here ServerConfigBuilderError.
+//
+// Raw identifiers such as `r#type` keep their setter name, or lose the `r#`
+// when a prefix is added.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(
+    name = "ServerConfigBuilder",
+    constructor = "configure",
+    build_fn(name = "finish"),
+    setter(prefix = "with_")
+)]
+pub struct ServerConfig {
+    host: String,
+    port: u16,
+    #[builder(each = "alias")]
+    aliases: Vec<String>,
+    #[builder(setter(prefix = "set_"))]
+    timeout: Option<u64>,
+}
+
+#[derive(Builder, Debug)]
+pub struct Listener {
+    r#type: String,
+    #[builder(setter(prefix = "with_"))]
+    r#loop: bool,
+}
+
+impl ServerConfig {
+    // The default names are free to be used by hand-written methods.
+    pub fn builder() -> &'static str {
+        "not generated"
+    }
+}
+
+fn main() {
+    let mut builder: ServerConfigBuilder = ServerConfig::configure();
+    let config = builder
+        .with_host("localhost".to_owned())
+        .with_port(8080)
+        .alias("local".to_owned())
+        .set_timeout(30)
+        .finish()
+        .unwrap();
+
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.aliases, ["local"]);
+    assert_eq!(config.timeout, Some(30));
+    assert_eq!(ServerConfig::builder(), "not generated");
+
+    let err: ServerConfigBuilderError = ServerConfig::configure().finish().unwrap_err();
+    assert_eq!(err.to_string(), "host, port are not provided");
+
+    let listener = Listener::builder()
+        .r#type("tcp".to_owned())
+        .with_loop(true)
+        .build()
+        .unwrap();
+    assert_eq!(listener.r#type, "tcp");
+    assert!(listener.r#loop);
+
+    let err = Listener::builder().build().unwrap_err();
+    assert_eq!(err.to_string(), "type, loop are not provided");
+}
@@ -0,0 +1,39 @@
+// Fields marked #[builder(skip)] are left out of the builder entirely: there
+// is no setter for them and they are never reported as missing. The build
+// function fills them with Default::default(), or with the given expression
+// for #[builder(skip = "...")].
+//
+// A skipped field cannot take any other builder option.
+
+use derive_builder::Builder;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+#[derive(Builder)]
+pub struct Connection<T> {
+    host: String,
+    #[builder(skip)]
+    requests: Cell<u64>,
+    #[builder(skip = "Vec::with_capacity(16)")]
+    buffer: Vec<u8>,
+    #[builder(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(Builder)]
+pub struct Pair(String, #[builder(skip = "42")] u32);
+
+fn main() {
+    let connection = Connection::<u8>::builder()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(connection.host, "localhost");
+    assert_eq!(connection.requests.get(), 0);
+    assert!(connection.buffer.capacity() >= 16);
+    let PhantomData = connection.marker;
+
+    let pair = Pair::builder()._0("answer".to_owned()).build().unwrap();
+    assert_eq!(pair.1, 42);
+}
@@ -0,0 +1,46 @@
+// Traits listed in #[builder(derive(...))] are derived for the builder, and
+// the attributes listed in #[builder(struct_attrs(...))] are put on the
+// builder struct as they are.
+//
+// Debug is not derived but implemented so that the output shows which fields
+// have been set and which are still unset. With the immutable pattern the
+// builder already implements Clone, so a requested Clone derive is skipped.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Debug, Clone, PartialEq), struct_attrs(must_use, allow(dead_code)))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable", derive(Clone, Debug))]
+pub struct Fixture {
+    name: String,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+
+    assert_eq!(
+        format!("{:?}", builder),
+        r#"CommandBuilder { executable: "cargo", args: ["build"], current_dir: <unset> }"#,
+    );
+
+    let snapshot = builder.clone();
+    assert_eq!(snapshot, builder);
+    builder.current_dir("/tmp".to_owned());
+    assert_ne!(snapshot, builder);
+    assert_eq!(
+        format!("{:?}", builder),
+        r#"CommandBuilder { executable: "cargo", args: ["build"], current_dir: Some("/tmp") }"#,
+    );
+
+    let fixture = Fixture::builder().clone();
+    assert_eq!(format!("{:?}", fixture), "FixtureBuilder { name: <unset> }");
+}
@@ -0,0 +1,44 @@
+// #[builder(try_setter)] generates, next to the setter, a try_<setter> method
+// that accepts any value convertible with TryInto. A failed conversion is
+// returned from the set call and leaves the field untouched.
+//
+// For an Option field the conversion targets the type that the setter takes,
+// so with strip_option (the default) it is the inner type.
+
+use derive_builder::Builder;
+use std::num::TryFromIntError;
+
+#[derive(Builder, Debug)]
+pub struct Server {
+    #[builder(try_setter)]
+    port: u16,
+    #[builder(try_setter)]
+    workers: Option<u8>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Client {
+    #[builder(try_setter)]
+    port: u16,
+}
+
+fn main() -> Result<(), TryFromIntError> {
+    let cli_port: u64 = 8080;
+    let server = Server::builder()
+        .try_port(cli_port)?
+        .try_workers(4u32)?
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, Some(4));
+
+    let mut builder = Server::builder();
+    assert!(builder.try_port(70000u32).is_err());
+    assert_eq!(builder.build().unwrap_err().to_string(), "port is not provided");
+
+    let client = Client::builder().try_port(443u64)?.build();
+    assert_eq!(client.port, 443);
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// With the `serde` cargo feature enabled, #[builder(serde)] makes the builder
+// implement Serialize and Deserialize with every field optional. A
+// configuration file can fill in part of a builder, and code sets the rest
+// before calling build. The typestate builder does not support this, because
+// its type would have to record which fields the file happened to contain.
+//
+// Fields are read and written under the same names as the struct, including
+// names given with #[serde(rename = "...")], the rename(serialize = "...",
+// deserialize = "...") form, and the rules of #[serde(rename_all = "...")] on
+// the struct or #[serde(rename_all_fields = "...")] on an enum. The build
+// function reports missing fields under the names used for deserializing.
+// Fields that are not set are left out when serializing. is_set accepts both
+// the serde name and the Rust name of a field.
+//
+// #[serde(alias = "...")] and the skip attributes of the struct's fields apply
+// to the builder too, so the builder accepts the same input as the struct.
+// Attributes working on values of the field type, like `with` or `default`,
+// are rejected.
+
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+#[derive(Builder, Serialize, Deserialize, Debug)]
+#[builder(serde)]
+pub struct Service {
+    name: String,
+    #[serde(rename = "listen-port")]
+    port: u16,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+    timeout: Option<u64>,
+}
+
+#[derive(Builder, Serialize, Deserialize, Debug)]
+#[builder(serde)]
+#[serde(rename_all = "camelCase")]
+pub struct Listener {
+    host_name: String,
+    listen_port: u16,
+    #[serde(rename(serialize = "tlsOut", deserialize = "tlsIn"))]
+    use_tls: bool,
+}
+
+#[derive(Builder, Serialize, Deserialize, Debug)]
+#[builder(serde)]
+pub struct Database {
+    #[serde(alias = "p")]
+    port: u16,
+    #[serde(skip)]
+    password: Option<String>,
+}
+
+#[derive(Builder, Serialize, Deserialize, Debug)]
+#[builder(serde)]
+#[serde(rename_all_fields = "kebab-case")]
+pub enum Source {
+    File {
+        file_path: String,
+    },
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    Env {
+        var_name: String,
+    },
+}
+
+fn main() {
+    let mut builder: ServiceBuilder = serde_json::from_str(r#"{ "listen-port": 8080 }"#).unwrap();
+    assert_eq!(
+        builder.build().unwrap_err().to_string(),
+        "name is not provided",
+    );
+
+    let mut builder: ServiceBuilder = serde_json::from_str(r#"{ "listen-port": 8080 }"#).unwrap();
+    let service = builder.name("api".to_owned()).tag("web".to_owned()).build().unwrap();
+    assert_eq!(service.name, "api");
+    assert_eq!(service.port, 8080);
+    assert_eq!(service.tags, ["web"]);
+    assert_eq!(service.timeout, None);
+
+    let err = Service::builder().build().unwrap_err();
+    assert_eq!(err.to_string(), "name, listen-port are not provided");
+
+    let mut builder = Service::builder();
+    builder.port(443).timeout(30);
+    assert_eq!(
+        serde_json::to_string(&builder).unwrap(),
+        r#"{"listen-port":443,"timeout":30}"#,
+    );
+
+    let mut builder: ListenerBuilder =
+        serde_json::from_str(r#"{ "listenPort": 80, "tlsIn": true }"#).unwrap();
+    assert!(builder.is_set("listenPort"));
+    assert!(builder.is_set("listen_port"));
+    assert!(!builder.is_set("hostName"));
+    assert_eq!(
+        serde_json::to_string(&builder).unwrap(),
+        r#"{"listenPort":80,"tlsOut":true}"#,
+    );
+    assert_eq!(
+        builder.build().unwrap_err().to_string(),
+        "hostName is not provided",
+    );
+    let listener = builder.host_name("localhost".to_owned()).build().unwrap();
+    assert_eq!(listener.listen_port, 80);
+    assert!(listener.use_tls);
+
+    assert_eq!(
+        Source::file_builder().build().unwrap_err().to_string(),
+        "file-path is not provided",
+    );
+    assert_eq!(
+        Source::env_builder().build().unwrap_err().to_string(),
+        "VAR_NAME is not provided",
+    );
+
+    let mut builder: DatabaseBuilder =
+        serde_json::from_str(r#"{ "p": 5432, "password": "secret" }"#).unwrap();
+    assert!(!builder.is_set("password"));
+    let database = builder.build().unwrap();
+    assert_eq!(database.port, 5432);
+    assert_eq!(database.password, None);
+}
@@ -0,0 +1,49 @@
+// The merge method layers one builder over another: every field that is set in
+// the other builder overrides the value in this one, and fields that are not
+// set there are left alone. This lets defaults, a configuration file,
+// environment variables and command-line flags each produce a partial builder.
+//
+// Collections are replaced by default. With #[builder(merge = "append")] the
+// items of the other builder are added to the ones already set.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder, Debug)]
+pub struct Config {
+    host: String,
+    port: u16,
+    timeout: Option<u64>,
+    #[builder(each = "include")]
+    includes: Vec<String>,
+    #[builder(each = "label", merge = "append")]
+    labels: HashMap<String, String>,
+}
+
+fn main() {
+    let mut defaults = Config::builder();
+    defaults
+        .host("localhost".to_owned())
+        .port(80)
+        .include("base.toml".to_owned())
+        .label("env".to_owned(), "dev".to_owned());
+
+    let mut file = Config::builder();
+    file.port(8080)
+        .timeout(30)
+        .include("service.toml".to_owned())
+        .label("team".to_owned(), "infra".to_owned());
+
+    let mut cli = Config::builder();
+    cli.label("env".to_owned(), "prod".to_owned());
+
+    let config = defaults.merge(file).merge(cli).build().unwrap();
+
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.timeout, Some(30));
+    assert_eq!(config.includes, ["service.toml"]);
+    assert_eq!(config.labels.len(), 2);
+    assert_eq!(config.labels["env"], "prod");
+    assert_eq!(config.labels["team"], "infra");
+}
@@ -0,0 +1,56 @@
+// An existing value can be turned back into a builder with every field
+// already set, either by consuming it with From/Into or by cloning it with
+// to_builder(). This makes it easy to copy a value and change a few fields.
+//
+// to_builder() is available when the struct implements Clone. For a typestate
+// builder all required fields are marked as set, so build can be called right
+// away.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct Config {
+    host: String,
+    timeout: u64,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+    #[builder(skip)]
+    hits: u32,
+}
+
+#[derive(Builder, Clone)]
+#[builder(typestate)]
+pub struct Request {
+    url: String,
+    retries: Option<u8>,
+}
+
+// No Clone: only From is usable.
+#[derive(Builder)]
+pub struct Token(String);
+
+fn main() {
+    let base = Config::builder()
+        .host("localhost".to_owned())
+        .timeout(30)
+        .tag("a".to_owned())
+        .build()
+        .unwrap();
+
+    let slow = base.to_builder().timeout(300).tag("b".to_owned()).build().unwrap();
+    assert_eq!(slow.host, "localhost");
+    assert_eq!(slow.timeout, 300);
+    assert_eq!(slow.tags, ["a", "b"]);
+    assert_eq!(base.timeout, 30);
+
+    let same = ConfigBuilder::from(base.clone()).build().unwrap();
+    assert_eq!(same, base);
+
+    let request = Request::builder().url("https://example.com".to_owned()).build();
+    let retried = request.to_builder().retries(3).build();
+    assert_eq!(retried.url, "https://example.com");
+    assert_eq!(retried.retries, Some(3));
+
+    let mut builder: TokenBuilder = Token("secret".to_owned()).into();
+    assert_eq!(builder.build().unwrap().0, "secret");
+}
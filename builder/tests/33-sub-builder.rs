@@ -0,0 +1,203 @@
+// A field whose type also derives Builder can be configured in place through
+// its own builder with #[builder(sub_builder)]. The setter takes a closure
+// that receives the inner builder, and the inner value is built together with
+// the outer one.
+//
+// Required fields missing in the inner builder are reported with the path of
+// the outer field, e.g. "tls.cert_path is not provided", together with the
+// missing fields of the outer builder. They are checked before any value is
+// taken out of the builder, so the builder can be completed and built again.
+// The inner builder
+// type defaults to the field type name followed by Builder, and can be given
+// explicitly with #[builder(sub_builder = "...")].
+//
+// The inner builder may use any build function name, its own error type, or
+// no_std without alloc. An error other than a missing field is reported as
+// ValidationFailed with the field name in front, so a custom error type needs
+// to implement Display.
+
+use std::fmt::{self, Display};
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct TlsConfig {
+    cert_path: String,
+    verify: Option<bool>,
+}
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct Limits {
+    #[builder(default = "64")]
+    connections: u32,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Server {
+    host: String,
+    #[builder(sub_builder)]
+    tls: TlsConfig,
+    #[builder(sub_builder = "LimitsBuilder", rename = "limits_with")]
+    limits: Limits,
+}
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(build_fn(name = "finish"), error = "RetryError")]
+pub struct Retry {
+    #[builder(validate = "check_attempts")]
+    attempts: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RetryError {
+    Builder(RetryBuilderError),
+    TooMany(u32),
+}
+
+impl From<RetryBuilderError> for RetryError {
+    fn from(err: RetryBuilderError) -> Self {
+        RetryError::Builder(err)
+    }
+}
+
+impl Display for RetryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RetryError::Builder(err) => err.fmt(f),
+            RetryError::TooMany(attempts) => write!(f, "{} attempts are too many", attempts),
+        }
+    }
+}
+
+fn check_attempts(attempts: &u32) -> Result<(), RetryError> {
+    if *attempts > 10 {
+        return Err(RetryError::TooMany(*attempts));
+    }
+    Ok(())
+}
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(no_std)]
+pub struct Backoff {
+    initial_ms: u64,
+    factor: u32,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Client {
+    #[builder(sub_builder)]
+    retry: Retry,
+    #[builder(sub_builder)]
+    backoff: Backoff,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(pattern = "owned")]
+pub struct Service {
+    name: String,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .tls(|tls| tls.cert_path("/etc/cert.pem".to_owned()).verify(true))
+        .build()
+        .unwrap();
+    assert_eq!(
+        server,
+        Server {
+            host: "localhost".to_owned(),
+            tls: TlsConfig {
+                cert_path: "/etc/cert.pem".to_owned(),
+                verify: Some(true),
+            },
+            limits: Limits { connections: 64 },
+        }
+    );
+
+    // The inner builder can be changed again by calling the setter twice.
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .tls(|tls| tls.cert_path("/etc/cert.pem".to_owned()))
+        .tls(|tls| tls.verify(false))
+        .limits_with(|limits| limits.connections(8))
+        .build()
+        .unwrap();
+    assert_eq!(server.tls.verify, Some(false));
+    assert_eq!(server.limits.connections, 8);
+
+    let err = Server::builder()
+        .host("localhost".to_owned())
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ServerBuilderError::MissingNestedFields(vec!["tls.cert_path".to_owned()])
+    );
+    assert_eq!(err.to_string(), "tls.cert_path is not provided");
+
+    // Missing fields of both builders are reported at once, and a failed build
+    // keeps the values that were set.
+    let mut builder = Server::builder();
+    builder.tls(|tls| tls.verify(true));
+    let err = builder.build().unwrap_err();
+    assert_eq!(err.to_string(), "host, tls.cert_path are not provided");
+    builder.host("localhost".to_owned());
+    let err = builder.build().unwrap_err();
+    assert_eq!(err.to_string(), "tls.cert_path is not provided");
+    builder.tls(|tls| tls.cert_path("/etc/cert.pem".to_owned()));
+    let server = builder.build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.tls.verify, Some(true));
+
+    // Paths are prefixed at every level.
+    let err = Service::builder()
+        .name("api".to_owned())
+        .server(|server| server.host("localhost".to_owned()))
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "server.tls.cert_path is not provided");
+
+    // Merging merges the inner builders instead of replacing them.
+    let mut base = Server::builder();
+    base.host("localhost".to_owned())
+        .tls(|tls| tls.cert_path("/etc/cert.pem".to_owned()));
+    let mut overrides = Server::builder();
+    overrides.tls(|tls| tls.verify(true));
+    let server = base.merge(overrides).build().unwrap();
+    assert_eq!(server.tls.cert_path, "/etc/cert.pem");
+    assert_eq!(server.tls.verify, Some(true));
+
+    // A value turned back into a builder holds builders for nested fields.
+    let mut builder = ServerBuilder::from(server);
+    builder.tls(|tls| tls.cert_path("/tmp/cert.pem".to_owned()));
+    let server = builder.build().unwrap();
+    assert_eq!(server.tls.cert_path, "/tmp/cert.pem");
+    assert_eq!(server.tls.verify, Some(true));
+
+    // Inner builders with a renamed build function, a custom error type, or
+    // without alloc.
+    let client = Client::builder()
+        .retry(|retry| retry.attempts(3))
+        .backoff(|backoff| backoff.initial_ms(100).factor(2))
+        .build()
+        .unwrap();
+    assert_eq!(client.retry, Retry { attempts: 3 });
+    assert_eq!(client.backoff.initial_ms, 100);
+
+    let err = Client::builder()
+        .retry(|retry| retry.attempts(3))
+        .backoff(|backoff| backoff.factor(2))
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "backoff.initial_ms is not provided");
+
+    let err = Client::builder()
+        .retry(|retry| retry.attempts(20))
+        .backoff(|backoff| backoff.initial_ms(100).factor(2))
+        .build()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "retry: 20 attempts are too many");
+}
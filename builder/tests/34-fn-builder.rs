@@ -0,0 +1,84 @@
+// The builder attribute turns a function with a long parameter list into a
+// call with named arguments. Parameters are classified like struct fields:
+// Option parameters may be omitted, Vec parameters support `each`, and
+// #[builder(...)] attributes on parameters work as they do on fields.
+//
+//     #[builder]
+//     fn connect(host: &str, port: u16, timeout: Option<Duration>) -> String
+//
+// generates a `connect_builder()` function returning a `ConnectBuilder`, and
+// `call()` invokes the function once every required parameter is set. A
+// forgotten parameter is a compile error, because the builder tracks the
+// required parameters in its type.
+//
+// Elided lifetimes in parameter types, like `&str`, are tied to the builder.
+
+use derive_builder::builder;
+use std::time::Duration;
+
+#[builder]
+fn connect(host: &str, port: u16, timeout: Option<Duration>) -> String {
+    match timeout {
+        Some(timeout) => format!("{}:{} ({}s)", host, port, timeout.as_secs()),
+        None => format!("{}:{}", host, port),
+    }
+}
+
+#[builder]
+pub fn command(
+    #[builder(setter(into))] program: String,
+    #[builder(each = "arg")] args: Vec<String>,
+    #[builder(default = "true")] inherit_env: bool,
+    #[builder(skip = "\"/\"")] cwd: &str,
+) -> Vec<String> {
+    let mut line = vec![program];
+    line.extend(args);
+    line.push(format!("env={} cwd={}", inherit_env, cwd));
+    line
+}
+
+#[builder]
+fn first<T: Clone>(items: &[T], fallback: T) -> T {
+    items.first().cloned().unwrap_or(fallback)
+}
+
+#[builder]
+fn parse<T: std::str::FromStr>(input: &str) -> Option<T> {
+    input.trim().parse().ok()
+}
+
+#[builder]
+async fn fetch(url: String, retries: Option<u8>) -> String {
+    format!("{} x{}", url, retries.unwrap_or(1))
+}
+
+fn main() {
+    let host = String::from("localhost");
+    assert_eq!(connect_builder().host(&host).port(80).call(), "localhost:80");
+    assert_eq!(
+        connect_builder()
+            .port(443)
+            .timeout(Duration::from_secs(5))
+            .host("example.com")
+            .call(),
+        "example.com:443 (5s)"
+    );
+
+    let line = command_builder()
+        .program("ls")
+        .arg("-l".to_owned())
+        .arg("-a".to_owned())
+        .call();
+    assert_eq!(line, ["ls", "-l", "-a", "env=true cwd=/"]);
+
+    assert_eq!(first_builder().items(&[3, 4]).fallback(0).call(), 3);
+    assert_eq!(first_builder().items(&[]).fallback(7).call(), 7);
+    assert_eq!(parse_builder::<u16>().input(" 8080 ").call(), Some(8080));
+
+    // The function itself is still available.
+    assert_eq!(connect("a", 1, None), "a:1");
+
+    // call() is async for async functions.
+    let future = fetch_builder().url("https://example.com".to_owned()).call();
+    let _: &dyn std::future::Future<Output = String> = &future;
+}
@@ -0,0 +1,84 @@
+// With #[builder(no_std)] the generated code only uses `core`, plus `alloc`
+// when the builder needs to allocate. This makes the derive usable in
+// #![no_std] crates.
+//
+// A builder whose fields include no collections (Vec, HashMap, ...) and no
+// sub-builders does not allocate at all. Its error type lists missing fields
+// in a fixed-size array together with the number of missing fields, and
+// validators report failures as `&'static str`.
+//
+// Other builders use `alloc` and need `extern crate alloc;` at the crate
+// root. #[builder(no_std(alloc))] selects `alloc` unconditionally, e.g. to
+// keep validators that return String.
+
+#![no_std]
+
+extern crate alloc;
+// Only used to run the test; the generated code must not refer to `std`.
+extern crate std as host_std;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std)]
+pub struct Pin {
+    number: u8,
+    port: u8,
+    pull_up: Option<bool>,
+    #[builder(validate = "check_speed")]
+    speed: u32,
+}
+
+fn check_speed(speed: &u32) -> Result<(), &'static str> {
+    if *speed > 100 {
+        Err("speed is too high")
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std)]
+pub struct Bus {
+    name: &'static str,
+    #[builder(each = "pin")]
+    pins: Vec<u8>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(no_std(alloc))]
+pub struct Led {
+    #[builder(validate = "check_pin")]
+    pin: u8,
+}
+
+fn check_pin(pin: &u8) -> Result<(), String> {
+    if *pin < 32 {
+        Ok(())
+    } else {
+        Err(alloc::format!("pin {} does not exist", pin))
+    }
+}
+
+fn main() {
+    let pin = Pin::builder().number(4).port(1).speed(10).build().unwrap();
+    assert_eq!(pin.pull_up, None);
+
+    let err = Pin::builder().speed(10).build().unwrap_err();
+    assert_eq!(err, PinBuilderError::MissingFields(["number", "port", ""], 2));
+    assert_eq!(err.to_string(), "number, port are not provided");
+
+    let err = Pin::builder().number(4).port(1).speed(1000).build().unwrap_err();
+    assert_eq!(err, PinBuilderError::ValidationFailed("speed is too high"));
+
+    // Bus has a Vec field, so its error type uses `alloc`.
+    let err = Bus::builder().pin(3).build().unwrap_err();
+    assert_eq!(err, BusBuilderError::MissingField("name"));
+    let bus = Bus::builder().name("i2c").pin(3).pin(4).build().unwrap();
+    assert_eq!(bus.pins, [3, 4]);
+
+    let err = Led::builder().pin(40).build().unwrap_err();
+    assert_eq!(err.to_string(), "pin 40 does not exist");
+}
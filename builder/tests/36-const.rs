@@ -0,0 +1,69 @@
+// With #[builder(const)] the builder can be used in constant expressions. The
+// builder uses the owned pattern, and its constructor, setters and build are
+// all `const fn`, so static configuration tables can be written with named
+// fields:
+//
+//     static CFG: Config = Config::builder().port(80).build();
+//
+// build() returns the value directly and panics if a required field is not
+// set; in a constant this becomes a compile error.
+//
+// Only constant defaults are possible, so #[builder(default = "...")] and
+// Option fields are allowed, while `each`, validators, `setter(into)` and
+// `builder(default)` without an expression are rejected.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(const)]
+pub struct Config {
+    name: &'static str,
+    port: u16,
+    #[builder(default = "30")]
+    timeout: u32,
+    proxy: Option<&'static str>,
+    #[builder(skip = "1")]
+    version: u8,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(const, setter(prefix = "with_"))]
+pub enum Route {
+    Static { path: &'static str },
+    Proxy(
+        #[builder(name = "target")] &'static str,
+        #[builder(name = "port")] Option<u16>,
+    ),
+}
+
+static CFG: Config = Config::builder().name("web").port(80).build();
+
+const ROUTES: [Route; 2] = [
+    Route::static_builder().with_path("/assets").build(),
+    Route::proxy_builder().with_target("backend").with_port(8080).build(),
+];
+
+fn main() {
+    assert_eq!(
+        CFG,
+        Config {
+            name: "web",
+            port: 80,
+            timeout: 30,
+            proxy: None,
+            version: 1,
+        }
+    );
+    assert_eq!(ROUTES[0], Route::Static { path: "/assets" });
+    assert_eq!(ROUTES[1], Route::Proxy("backend", Some(8080)));
+
+    // The builder is still usable at runtime.
+    let config = Config::builder()
+        .name("api")
+        .port(8080)
+        .proxy("proxy.local")
+        .timeout(5)
+        .build();
+    assert_eq!(config.proxy, Some("proxy.local"));
+    assert_eq!(config.timeout, 5);
+}
@@ -0,0 +1,17 @@
+// A const builder reports a missing required field when the constant is
+// evaluated, so forgetting a field in a static is a compile error.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Config {
+    name: &'static str,
+    port: u16,
+}
+
+static CFG: Config = Config::builder().name("web").build();
+
+fn main() {
+    let _ = CFG.port;
+}
@@ -0,0 +1,133 @@
+// Every builder describes its fields at runtime. FooBuilder::FIELDS lists
+// each field's name, type, kind, whether it has a default, and its doc
+// comment. This is enough to generate --help text or configuration docs.
+//
+// is_set(name) tells whether a field has a value, and missing_fields()
+// returns the names of the required fields that are still unset as a Vec, so
+// users can be told what is missing before build() is called. Required fields
+// of inner builders are listed with their paths, like "tls.cert". Without alloc,
+// missing_fields() returns an array with room for every required field and
+// the number of names in it.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder)]
+pub struct Server {
+    /// Host name to bind.
+    host: String,
+    /// Port to listen on.
+    ///
+    /// Ports below 1024 need privileges.
+    port: u16,
+    #[builder(default = "30")]
+    timeout: u64,
+    proxy: Option<String>,
+    #[builder(each = "header")]
+    headers: HashMap<String, Vec<String>>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request<'a> {
+    url: &'a str,
+    body: Option<&'a [u8]>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Route<'a> {
+    path: &'a str,
+    handler: &'a str,
+    method: Option<&'a str>,
+}
+
+#[derive(Builder)]
+pub struct Tls {
+    cert: String,
+    key: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct Gateway {
+    name: String,
+    #[builder(sub_builder)]
+    tls: Tls,
+}
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Pin {
+    number: u8,
+    label: &'static str,
+    pull_up: Option<bool>,
+}
+
+fn main() {
+    let fields = ServerBuilder::FIELDS;
+    assert_eq!(fields.len(), 5);
+
+    assert_eq!(fields[0].name, "host");
+    assert_eq!(fields[0].ty, "String");
+    assert_eq!(fields[0].kind, ServerBuilderFieldKind::Required);
+    assert!(!fields[0].has_default);
+    assert_eq!(fields[0].doc, "Host name to bind.");
+
+    assert_eq!(
+        fields[1].doc,
+        "Port to listen on.\n\nPorts below 1024 need privileges."
+    );
+
+    assert_eq!(fields[2].kind, ServerBuilderFieldKind::Optional);
+    assert!(fields[2].has_default);
+    assert_eq!(fields[2].doc, "");
+
+    assert_eq!(fields[3].ty, "Option<String>");
+    assert_eq!(fields[3].kind, ServerBuilderFieldKind::Optional);
+
+    assert_eq!(fields[4].ty, "HashMap<String, Vec<String>>");
+    assert_eq!(fields[4].kind, ServerBuilderFieldKind::Collection);
+
+    let mut builder = Server::builder();
+    assert!(!builder.is_set("host"));
+    assert_eq!(builder.missing_fields(), ["host", "port"]);
+
+    builder.host("localhost".to_owned()).timeout(5);
+    assert!(builder.is_set("host"));
+    assert!(builder.is_set("timeout"));
+    assert!(!builder.is_set("unknown"));
+    assert_eq!(builder.missing_fields(), ["port"]);
+
+    builder.port(80);
+    assert!(builder.missing_fields().is_empty());
+
+    // Typestate builders describe their fields too.
+    assert_eq!(RequestBuilder::FIELDS[0].ty, "&'a str");
+    assert_eq!(RequestBuilder::FIELDS[1].ty, "Option<&'a [u8]>");
+    let builder = Request::builder();
+    assert_eq!(builder.missing_fields(), ["url"]);
+    let builder = builder.url("https://example.com");
+    assert!(builder.is_set("url"));
+    assert!(builder.missing_fields().is_empty());
+
+    // Builders borrowing their values work with every pattern.
+    let path = String::from("/users");
+    let builder = Route::builder().path(&path);
+    assert_eq!(builder.missing_fields(), ["handler"]);
+    let builder = builder.handler("list_users");
+    assert!(builder.missing_fields().is_empty());
+
+    let mut builder = Gateway::builder();
+    assert_eq!(builder.missing_fields(), ["name", "tls.cert"]);
+    builder.tls(|tls| tls.key("key.pem".to_owned()));
+    assert_eq!(builder.missing_fields(), ["name", "tls.cert"]);
+    builder.tls(|tls| tls.cert("cert.pem".to_owned()));
+    assert_eq!(builder.missing_fields(), ["name"]);
+
+    let mut builder = Pin::builder();
+    builder.label("led");
+    let (missing, len) = builder.missing_fields();
+    assert_eq!(&missing[..len], ["number"]);
+    builder.number(13);
+    assert_eq!(builder.missing_fields().1, 0);
+}
@@ -0,0 +1,17 @@
+// A field configured through an inner builder is always merged by merging the
+// inner builders, so #[builder(merge = "...")] cannot be given for it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Tags {
+    values: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Post {
+    #[builder(sub_builder, merge = "append")]
+    tags: Tags,
+}
+
+fn main() {}
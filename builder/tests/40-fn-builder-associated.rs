@@ -0,0 +1,23 @@
+// The builder for a function is defined next to the function, and an impl
+// block cannot contain type definitions. Using #[builder] on an associated
+// function, recognized by `Self` in its signature, is reported with a single
+// error, and the function itself is kept so that its callers still compile.
+
+use derive_builder::builder;
+
+pub struct Connection {
+    host: String,
+    port: u16,
+}
+
+impl Connection {
+    #[builder]
+    pub fn new(host: String, #[builder(default = "80")] port: u16) -> Self {
+        Connection { host, port }
+    }
+}
+
+fn main() {
+    let connection = Connection::new("localhost".to_owned(), 80);
+    assert_eq!((connection.host.as_str(), connection.port), ("localhost", 80));
+}
@@ -0,0 +1,14 @@
+// A typestate builder records in its type which required fields have been
+// set. Deriving Default would allow creating a builder whose type claims every
+// field is set without setting any of them, so #[builder(derive(Default))]
+// cannot be combined with #[builder(typestate)].
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate, derive(Debug, Default))]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
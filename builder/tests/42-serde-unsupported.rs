@@ -0,0 +1,15 @@
+// The builder stores each field as an Option, so serde attributes that work on
+// values of the field type cannot be applied to it. Rather than silently
+// accepting a different format than the struct, they are rejected.
+
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+#[derive(Builder, Serialize, Deserialize)]
+#[builder(serde)]
+pub struct Service {
+    #[serde(default)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// #[builder(serde)] generates code for the serde crate, which is only enabled
+// with the `serde` cargo feature of this crate. Without it the attribute is
+// reported instead of failing inside the generated code.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(serde)]
+pub struct Service {
+    name: String,
+}
+
+fn main() {}
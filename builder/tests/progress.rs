@@ -10,4 +10,41 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-typed-error.rs");
+    t.pass("tests/11-typestate.rs");
+    t.compile_fail("tests/12-typestate-missing-field.rs");
+    t.pass("tests/13-generics.rs");
+    t.pass("tests/14-tuple-struct-and-enum.rs");
+    t.pass("tests/15-default.rs");
+    t.pass("tests/16-validate.rs");
+    t.pass("tests/17-setter-into.rs");
+    t.pass("tests/18-pattern.rs");
+    t.pass("tests/19-each-collections.rs");
+    t.pass("tests/20-qualified-paths.rs");
+    t.pass("tests/21-multiple-attributes.rs");
+    t.compile_fail("tests/22-duplicate-attribute.rs");
+    t.pass("tests/23-extend.rs");
+    t.compile_fail("tests/24-method-collision.rs");
+    t.pass("tests/25-visibility.rs");
+    t.pass("tests/26-names.rs");
+    t.pass("tests/27-skip.rs");
+    t.pass("tests/28-derive.rs");
+    t.pass("tests/29-try-setter.rs");
+    #[cfg(feature = "serde")]
+    t.pass("tests/30-serde.rs");
+    t.pass("tests/31-merge.rs");
+    t.pass("tests/32-to-builder.rs");
+    t.pass("tests/33-sub-builder.rs");
+    t.pass("tests/34-fn-builder.rs");
+    t.pass("tests/35-no-std.rs");
+    t.pass("tests/36-const.rs");
+    t.compile_fail("tests/37-const-missing-field.rs");
+    t.pass("tests/38-field-info.rs");
+    t.compile_fail("tests/39-sub-builder-merge.rs");
+    t.compile_fail("tests/40-fn-builder-associated.rs");
+    t.compile_fail("tests/41-typestate-derive-default.rs");
+    #[cfg(feature = "serde")]
+    t.compile_fail("tests/42-serde-unsupported.rs");
+    #[cfg(not(feature = "serde"))]
+    t.compile_fail("tests/43-serde-feature.rs");
 }